@@ -11,6 +11,12 @@ pub struct CodecAttributes {
     pub extension: String,
     pub codec_type: CodecType,
     pub params: Vec<CodecParam>,
+    /// An argument template for a pipe-oriented external encoder/decoder,
+    /// e.g. `"opusenc --raw --raw-rate {sample_rate} --raw-chan {channels} - -"`.
+    /// `{mode}`, `{sample_rate}`, and `{channels}` are substituted before the
+    /// template is split on whitespace into a program and its args; PCM/
+    /// encoded bytes are piped through the spawned program's stdin/stdout
+    /// rather than round-tripped through temp files.
     pub external_program: Option<String>,
     pub requires_model: bool,
 }
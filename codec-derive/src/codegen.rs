@@ -102,9 +102,13 @@ impl CodecImpl {
                 }
             }
         } else {
-            quote! {
-                fn encode(&self, data: &[f32], sample_rate: u32) -> Result<Vec<u8>>;
-            }
+            // Neither an external program nor a model to shell out to: the
+            // struct is expected to implement `CodecImpl::encode_samples`
+            // directly, so leave `Codec::encode` unoverridden and let its
+            // default (which calls `encode_samples`) apply. Emitting a
+            // signature here instead of nothing would be a bodiless method
+            // inside a trait `impl` block, which doesn't compile.
+            quote! {}
         }
     }
 
@@ -122,55 +126,97 @@ impl CodecImpl {
                 }
             }
         } else {
-            quote! {
-                fn decode(&self, data: &[u8], sample_rate: u32) -> Result<Vec<f32>>;
-            }
+            // See `generate_encode_impl`: fall through to `Codec::decode`'s
+            // default, which calls `CodecImpl::decode_samples`.
+            quote! {}
         }
     }
 
-    fn generate_external_program_impl(&self, program: &str) -> TokenStream {
+    /// `template` is the `external_program` attribute value, e.g.
+    /// `"opusenc --raw --raw-rate {sample_rate} --raw-chan {channels} - -"`:
+    /// whitespace-split into a program and its args after substituting
+    /// `{mode}` (`"encode"`/`"decode"`), `{sample_rate}`, and `{channels}`,
+    /// so a single attribute can describe either direction of a pipe-
+    /// oriented tool without a fixed CLI convention baked into the macro.
+    fn generate_external_program_impl(&self, template: &str) -> TokenStream {
         let struct_name = &self.name;
         quote! {
             impl #struct_name {
-                fn encode_external(&self, data: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
+                fn spawn_external(&self, mode: &str, sample_rate: u32) -> Result<std::process::Child> {
                     use std::process::{Command, Stdio};
-                    use std::io::Write;
-                    use tempfile::NamedTempFile;
 
-                    let mut temp_wav = NamedTempFile::new()?;
-                    // Convert to WAV first
-                    let wav_data = WavCodec::default().encode(data, sample_rate)?;
-                    temp_wav.write_all(&wav_data)?;
-
-                    let output = Command::new(#program)
-                        .arg(temp_wav.path())
-                        .output()?;
+                    let rendered = #template
+                        .replace("{mode}", mode)
+                        .replace("{sample_rate}", &sample_rate.to_string())
+                        .replace("{channels}", "1");
+                    let mut parts = rendered.split_whitespace();
+                    let program = parts
+                        .next()
+                        .ok_or_else(|| Error::Codec("Empty external_program template".to_string()))?;
+
+                    Command::new(program)
+                        .args(parts)
+                        .stdin(Stdio::piped())
+                        .stdout(Stdio::piped())
+                        .stderr(Stdio::piped())
+                        .spawn()
+                        .map_err(|e| Error::Codec(format!("Failed to spawn {}: {}", program, e)))
+                }
 
-                    if !output.status.success() {
-                        return Err(Error::Codec(format!("{} encoding failed", #program)));
+                /// Pipes `input` through the external program's stdin/stdout,
+                /// writing stdin and draining stderr on their own threads so
+                /// a program that interleaves output across all three
+                /// streams (e.g. logging verbosely to stderr while still
+                /// writing to stdout) can't fill an undrained pipe buffer
+                /// and deadlock the blocking stdout read.
+                fn run_external(&self, mode: &str, sample_rate: u32, input: &[u8]) -> Result<Vec<u8>> {
+                    use std::io::{Read, Write};
+
+                    let mut child = self.spawn_external(mode, sample_rate)?;
+                    let mut stdin = child.stdin.take().expect("child stdin was requested as piped");
+                    let input = input.to_vec();
+                    let writer = std::thread::spawn(move || {
+                        let _ = stdin.write_all(&input);
+                    });
+
+                    let mut stderr_pipe = child.stderr.take().expect("child stderr was requested as piped");
+                    let stderr_reader = std::thread::spawn(move || {
+                        let mut stderr = String::new();
+                        let _ = stderr_pipe.read_to_string(&mut stderr);
+                        stderr
+                    });
+
+                    let mut stdout = Vec::new();
+                    let stdout_result = child
+                        .stdout
+                        .take()
+                        .expect("child stdout was requested as piped")
+                        .read_to_end(&mut stdout)
+                        .map_err(|e| Error::Codec(format!("Failed to read external program output: {}", e)));
+
+                    let _ = writer.join();
+                    let stderr = stderr_reader.join().unwrap_or_default();
+                    stdout_result?;
+
+                    let status = child
+                        .wait()
+                        .map_err(|e| Error::Codec(format!("Failed to wait on external program: {}", e)))?;
+
+                    if !status.success() {
+                        return Err(Error::Codec(format!("external program {} failed: {}", mode, stderr)));
                     }
 
-                    Ok(output.stdout)
+                    Ok(stdout)
                 }
 
-                fn decode_external(&self, data: &[u8], sample_rate: u32) -> Result<Vec<f32>> {
-                    use std::process::{Command, Stdio};
-                    use std::io::Write;
-                    use tempfile::NamedTempFile;
-
-                    let mut temp_input = NamedTempFile::new()?;
-                    temp_input.write_all(data)?;
-
-                    let output = Command::new(#program)
-                        .arg("--decode")
-                        .arg(temp_input.path())
-                        .output()?;
-
-                    if !output.status.success() {
-                        return Err(Error::Codec(format!("{} decoding failed", #program)));
-                    }
+                fn encode_external(&self, data: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
+                    let wav_data = WavCodec::default().encode(data, sample_rate)?;
+                    self.run_external("encode", sample_rate, &wav_data)
+                }
 
-                    WavCodec::default().decode(&output.stdout, sample_rate)
+                fn decode_external(&self, data: &[u8], sample_rate: u32) -> Result<Vec<f32>> {
+                    let output = self.run_external("decode", sample_rate, data)?;
+                    WavCodec::default().decode(&output, sample_rate)
                 }
             }
         }
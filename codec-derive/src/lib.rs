@@ -86,7 +86,7 @@ fn generate_test_module(
             use super::*;
             use crate::prelude::*;
             use crate::audio::codec::{
-                test_utils::{AudioTestSuite, AudioQualityMetrics, AudioCategory},
+                test_utils::{AudioTestSuite, AudioQualityMetrics, AudioCategory, encoded_digest},
                 traits::CodecPerformance,
             };
 
@@ -131,7 +131,7 @@ fn generate_test_module(
                         test_case.name
                     );
 
-                    let metrics = AudioQualityMetrics::calculate(&normalized, &decoded);
+                    let metrics = AudioQualityMetrics::calculate(&normalized, &decoded, test_case.sample_rate);
 
                     // Print metrics before assertions
                     println!("Quality metrics for {}:", test_case.name);
@@ -229,6 +229,79 @@ fn generate_test_module(
 
                 Ok(())
             }
+
+            /// Hash each case's encoded output (plus decoded samples, for
+            /// lossless codecs) and compare against the golden digest
+            /// checked into `data/digests/{codec}_{case}.sha256`.
+            ///
+            /// The first run for a case that has no committed digest file
+            /// writes one (record mode) instead of asserting — inspect it
+            /// (dump the sample via `output_file` and check it with an
+            /// external decoder) and commit the file so later runs actually
+            /// compare against it.
+            #[test]
+            fn test_golden_digests() -> Result<()> {
+                let codec = #codec_type::default();
+                let test_suite = AudioTestSuite::load_default_cases()?;
+                let digests_dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                    .join("data")
+                    .join("digests");
+
+                for test_case in test_suite.iter() {
+                    let encoded = codec.encode_samples(&test_case.samples, test_case.sample_rate)?;
+
+                    if let Some(path) = test_case.output_file {
+                        std::fs::write(path, &encoded).map_err(|e| {
+                            crate::error::CodecError::Encoding(format!(
+                                "Failed to dump sample to {}: {}", path, e
+                            ))
+                        })?;
+                    }
+
+                    let decoded = if #is_lossy {
+                        None
+                    } else {
+                        Some(codec.decode_samples(&encoded, test_case.sample_rate)?)
+                    };
+                    let digest = encoded_digest(&encoded, decoded.as_deref());
+
+                    let key = format!("{}/{}", #codec_name, test_case.name);
+                    let file_name = format!(
+                        "{}_{}.sha256",
+                        #codec_name.to_lowercase(),
+                        test_case.name.to_lowercase().replace(' ', "_"),
+                    );
+                    let digest_path = digests_dir.join(file_name);
+
+                    match std::fs::read_to_string(&digest_path) {
+                        Ok(golden) => assert_eq!(
+                            digest, golden.trim(),
+                            "Digest drift for {} (run with output_file to inspect); \
+                             if this change is intentional, delete {} and re-run to re-record it",
+                            key, digest_path.display(),
+                        ),
+                        Err(_) => {
+                            std::fs::create_dir_all(&digests_dir).map_err(|e| {
+                                crate::error::CodecError::Encoding(format!(
+                                    "Failed to create {}: {}", digests_dir.display(), e
+                                ))
+                            })?;
+                            std::fs::write(&digest_path, &digest).map_err(|e| {
+                                crate::error::CodecError::Encoding(format!(
+                                    "Failed to record golden digest to {}: {}",
+                                    digest_path.display(), e
+                                ))
+                            })?;
+                            println!(
+                                "Recorded golden digest for {} at {}: {}",
+                                key, digest_path.display(), digest
+                            );
+                        }
+                    }
+                }
+
+                Ok(())
+            }
         }
     }
 }
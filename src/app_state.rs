@@ -1,14 +1,17 @@
 use crate::audio::buffer::AudioBuffer;
+use crate::audio::mixer::{AudioMixer, SourceHandle};
+use crate::audio::processor::DeviceCommand;
 use crate::config::CONFIG_MANAGER;
 use crate::prelude::*;
-use crate::storage::{LocalStorage, S3Storage, StorageManager};
+use crate::storage::{ChaCha20Poly1305Cipher, DbIndex, LocalStorage, S3Storage, StorageManager};
 use dotenv::dotenv;
 use std::env;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex as StdMutex, RwLock as StdRwLock};
 use std::time::SystemTime;
 use tokio::sync::broadcast;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
 use tokio::time::Duration;
 use tracing::{error, info, warn};
 
@@ -17,6 +20,38 @@ pub struct AppState {
     pub audio_sender: broadcast::Sender<Vec<f32>>,
     pub start_time: SystemTime,
     pub storage_manager: Arc<StorageManager>,
+    /// Stable identifier (device name) of the active input device, or `None`
+    /// to use the host default.
+    pub active_device: StdRwLock<Option<String>>,
+    /// Sender used by handlers to request a live device switch.
+    pub device_control: mpsc::Sender<DeviceCommand>,
+    /// Receiver handed to the stream management task on startup.
+    pub device_rx: StdMutex<Option<mpsc::Receiver<DeviceCommand>>>,
+    /// Deterministic-testing facade: staged input injection and output capture.
+    pub recording: Arc<RecordingControl>,
+    /// Sums every registered input source into `audio_buffer` on a fixed
+    /// cadence; the live microphone capture is itself just the first
+    /// registered source (`mic_source`), leaving room to mix in e.g. a
+    /// system-loopback source later without touching the capture path.
+    pub mixer: Arc<AudioMixer>,
+    /// Handle the audio capture callback feeds with live microphone samples.
+    pub mic_source: SourceHandle,
+}
+
+/// Facade for driving known audio into the buffer and tee-ing the buffer's
+/// output into a side recording, so integration tests can assert on encoded
+/// output without a physical microphone.
+#[derive(Default)]
+pub struct RecordingControl {
+    /// Decoded samples staged by `PUT /input_audio`, ready to inject.
+    pub staged_input: StdMutex<Vec<f32>>,
+    /// While set, the injection task feeds `staged_input` into the buffer in
+    /// place of live capture.
+    pub injecting: AtomicBool,
+    /// While set, samples flowing through the buffer are tee'd into `output`.
+    pub output_saving: AtomicBool,
+    /// The captured side recording returned by `GET /output_audio`.
+    pub output: StdMutex<Vec<f32>>,
 }
 
 impl AppState {
@@ -53,22 +88,56 @@ impl AppState {
             }
         };
 
+        let db_index = DbIndex::open(&PathBuf::from("./data/audio_storage/segments.sqlite3"))?;
+
+        // Transparent at-rest encryption, enabled via a 32-byte hex key in
+        // `[encryption]` in Config (or LIFELOGGING__ENCRYPTION__KEY), with a
+        // fallback to the legacy LIFELOGGING_ENCRYPTION_KEY env var for
+        // deployments that haven't migrated yet. Off by default so existing
+        // deployments aren't surprised by unreadable archives.
+        let cipher = match config.read().await.encryption.clone() {
+            Some(settings) => Some(ChaCha20Poly1305Cipher::from_hex_key(&settings.key)?),
+            None => ChaCha20Poly1305Cipher::from_env()?,
+        }
+        .map(Arc::new);
+        if cipher.is_some() {
+            info!("At-rest encryption enabled for stored audio segments");
+        }
+
         let storage_manager = Arc::new(StorageManager::new(
             local_storage,
             s3_storage,
+            db_index,
             Duration::from_secs(config.read().await.buffer_duration),
             48000,
             "opus".to_string(),
+            cipher,
         ));
 
+        let (device_control, device_rx) = mpsc::channel(4);
+
+        let audio_buffer = Arc::new(RwLock::new(AudioBuffer::new(
+            buffer_size,
+            stream_config.sample_rate.0,
+        )));
+
+        // 20ms frames, matching the cadence `inject_staged_audio` already
+        // uses for its synthetic input.
+        let mixer_frame_size = (stream_config.sample_rate.0 as usize / 50).max(1);
+        let mixer = Arc::new(AudioMixer::new(audio_buffer.clone(), mixer_frame_size));
+        let mic_source = mixer.add_source(stream_config.sample_rate.0);
+
         Ok(AppState {
-            audio_buffer: Arc::new(RwLock::new(AudioBuffer::new(
-                buffer_size,
-                stream_config.sample_rate.0,
-            ))),
+            audio_buffer,
             audio_sender: broadcast::channel(1024).0,
             start_time: SystemTime::now(),
             storage_manager,
+            active_device: StdRwLock::new(None),
+            device_control,
+            device_rx: StdMutex::new(Some(device_rx)),
+            recording: Arc::new(RecordingControl::default()),
+            mixer,
+            mic_source,
         })
     }
 }
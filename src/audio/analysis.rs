@@ -0,0 +1,94 @@
+//! Short-time Fourier transform spectrogram analysis over the rolling audio
+//! buffer.
+//!
+//! `AudioBuffer::read` only hands back time-domain samples; `Spectrogram`
+//! turns a slice of them into frequency-domain magnitudes suitable for
+//! rendering: the samples are sliced into overlapping, Hann-windowed
+//! frames, each transformed with a real-to-complex FFT (`realfft`), and
+//! each bin's magnitude collected per frame.
+
+use realfft::RealFftPlanner;
+
+/// STFT parameters: an FFT `window` size and the `hop` between consecutive
+/// frames. A 50% overlap (`hop == window / 2`) is a reasonable default.
+#[derive(Debug, Clone, Copy)]
+pub struct Spectrogram {
+    window: usize,
+    hop: usize,
+}
+
+impl Spectrogram {
+    pub fn new(window: usize, hop: usize) -> Self {
+        Self { window, hop }
+    }
+
+    /// The center frequency in Hz of each of the `window / 2 + 1` bins a
+    /// transform at `sample_rate` produces.
+    pub fn bin_frequencies(&self, sample_rate: u32) -> Vec<f32> {
+        let bins = self.window / 2 + 1;
+        (0..bins)
+            .map(|bin| bin as f32 * sample_rate as f32 / self.window as f32)
+            .collect()
+    }
+
+    /// Slice `data` into overlapping Hann-windowed frames and return each
+    /// frame's FFT bin magnitudes as `[frame][bin]`. Shorter than one
+    /// `window` returns no frames.
+    pub fn magnitudes(&self, data: &[f32]) -> Vec<Vec<f32>> {
+        if data.len() < self.window || self.hop == 0 {
+            return Vec::new();
+        }
+
+        let mut planner = RealFftPlanner::<f32>::new();
+        let fft = planner.plan_fft_forward(self.window);
+        let hann = hann_window(self.window);
+
+        let mut frames = Vec::new();
+        let mut start = 0;
+        while start + self.window <= data.len() {
+            let mut input = fft.make_input_vec();
+            for (i, &sample) in data[start..start + self.window].iter().enumerate() {
+                input[i] = sample * hann[i];
+            }
+
+            let mut output = fft.make_output_vec();
+            fft.process(&mut input, &mut output)
+                .expect("input/output buffers sized by the planner itself");
+
+            frames.push(
+                output
+                    .iter()
+                    .map(|c| (c.re * c.re + c.im * c.im).sqrt())
+                    .collect(),
+            );
+
+            start += self.hop;
+        }
+
+        frames
+    }
+}
+
+impl Default for Spectrogram {
+    /// 1024-sample window with 50% overlap, a common default for
+    /// speech/music spectrograms at typical capture rates.
+    fn default() -> Self {
+        Self::new(1024, 512)
+    }
+}
+
+/// Convert a linear FFT-bin magnitude to decibels (`20 * log10(mag)`),
+/// flooring at a small epsilon so silence doesn't produce `-inf`.
+pub fn magnitude_to_db(magnitude: f32) -> f32 {
+    20.0 * magnitude.max(1e-10).log10()
+}
+
+/// `0.5 - 0.5*cos(2πn/(N-1))`, tapering frame edges to limit the spectral
+/// leakage a rectangular window would introduce.
+fn hann_window(n: usize) -> Vec<f32> {
+    (0..n)
+        .map(|i| {
+            0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (n - 1) as f32).cos()
+        })
+        .collect()
+}
@@ -1,3 +1,4 @@
+use crate::audio::resample::{resample, InterpolationMode};
 use crate::prelude::*;
 use std::ptr;
 use std::time::Duration;
@@ -105,6 +106,9 @@ impl<T: Copy + Default> CircularBuffer<T> {
 pub struct AudioBuffer {
     buffer: CircularBuffer<f32>,
     sample_rate: u32,
+    /// Total number of samples ever written, used as a monotonic cursor by
+    /// streaming consumers to read only newly-captured audio.
+    total_written: u64,
 }
 
 impl AudioBuffer {
@@ -116,15 +120,34 @@ impl AudioBuffer {
         AudioBuffer {
             buffer: CircularBuffer::new(capacity),
             sample_rate,
+            total_written: 0,
         }
     }
 
     pub fn write(&mut self, data: &[f32]) {
         self.buffer.write(data);
+        self.total_written += data.len() as u64;
     }
 
     pub fn write_fast(&mut self, data: &[f32]) {
         self.buffer.write_fast(data);
+        self.total_written += data.len() as u64;
+    }
+
+    /// The monotonic count of samples ever written. A streaming client records
+    /// this as a cursor and passes it back to [`read_since`](Self::read_since).
+    pub fn total_written(&self) -> u64 {
+        self.total_written
+    }
+
+    /// Return every sample written since `cursor` together with the new cursor.
+    ///
+    /// If the producer has outrun the ring capacity since `cursor` was taken,
+    /// only the most recent `capacity` samples survive and are returned.
+    pub fn read_since(&self, cursor: u64) -> (Vec<f32>, u64) {
+        let available = self.total_written.saturating_sub(cursor);
+        let count = (available as usize).min(self.buffer.capacity);
+        (self.buffer.read(count), self.total_written)
     }
 
     pub fn read(&self, duration: Option<Duration>) -> Vec<f32> {
@@ -143,26 +166,16 @@ impl AudioBuffer {
             return Ok(());
         }
 
-        let new_capacity = (self.buffer.capacity as f32 * new_sample_rate as f32
-            / self.sample_rate as f32)
-            .ceil() as usize;
         let old_data = self.buffer.read(self.buffer.capacity);
-
-        // Resample the existing data
-        let new_data: Vec<f32> = (0..new_capacity)
-            .map(|i| {
-                let old_index = i as f32 * self.sample_rate as f32 / new_sample_rate as f32;
-                let old_index_floor = old_index.floor() as usize;
-                let old_index_ceil = old_index.ceil() as usize;
-                let frac = old_index - old_index.floor();
-
-                if old_index_ceil >= old_data.len() {
-                    old_data[old_index_floor]
-                } else {
-                    old_data[old_index_floor] * (1.0 - frac) + old_data[old_index_ceil] * frac
-                }
-            })
-            .collect();
+        // Band-limited windowed-sinc resampling avoids the aliasing/high-end
+        // smearing a naive linear interpolator would introduce here.
+        let new_data = resample(
+            &old_data,
+            self.sample_rate,
+            new_sample_rate,
+            InterpolationMode::PolyphaseSinc,
+        );
+        let new_capacity = new_data.len();
 
         self.buffer = CircularBuffer::new(new_capacity);
         self.buffer.write(&new_data);
@@ -179,4 +192,41 @@ impl AudioBuffer {
     pub fn get_sample_rate(&self) -> u32 {
         self.sample_rate
     }
+
+    /// The number of samples the ring can hold, i.e. the span of history
+    /// still available to [`read_range_ms`](Self::read_range_ms).
+    pub fn capacity(&self) -> usize {
+        self.buffer.capacity
+    }
+
+    /// Samples covering `[now - start_ms, now - end_ms)`, i.e. the window
+    /// starting `start_ms` milliseconds back from the most recently written
+    /// sample and ending `end_ms` milliseconds back (`start_ms > end_ms`).
+    /// Clamped to whatever the ring still holds, so a window reaching
+    /// further back than the buffer's capacity is silently truncated at the
+    /// oldest available sample instead of erroring.
+    pub fn read_range_ms(&self, start_ms: u64, end_ms: u64) -> Vec<f32> {
+        let start_samples = ms_to_samples(start_ms, self.sample_rate).min(self.buffer.capacity);
+        let end_samples = ms_to_samples(end_ms, self.sample_rate).min(self.buffer.capacity);
+
+        if start_samples <= end_samples {
+            return Vec::new();
+        }
+
+        let window = self.buffer.read(start_samples);
+        let keep = window.len() - end_samples.min(window.len());
+        window[..keep].to_vec()
+    }
+}
+
+/// `sample_index = round(ms * sample_rate / 1000)`, the one place buffer and
+/// storage retrieval convert a millisecond offset to a sample count so the
+/// two don't drift apart.
+pub fn ms_to_samples(ms: u64, sample_rate: u32) -> usize {
+    (ms as f64 * sample_rate as f64 / 1000.0).round() as usize
+}
+
+/// Inverse of [`ms_to_samples`]: `ms = sample_index * 1000 / sample_rate`.
+pub fn samples_to_ms(samples: usize, sample_rate: u32) -> u64 {
+    (samples as f64 * 1000.0 / sample_rate as f64).round() as u64
 }
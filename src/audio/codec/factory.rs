@@ -1,5 +1,6 @@
 use super::flac::FlacCodec;
 use super::moshi::MoshiCodec;
+use super::mp3::Mp3Codec;
 use super::opus::OpusCodec;
 use super::traits::Codec;
 use super::wav::WavCodec;
@@ -28,6 +29,10 @@ impl CodecFactory {
             "opus".into(),
             Arc::new(OpusCodec::default()) as Arc<dyn Codec>,
         );
+        codecs.insert(
+            "mp3".into(),
+            Arc::new(Mp3Codec::default()) as Arc<dyn Codec>,
+        );
         codecs.insert(
             "moshi".into(),
             Arc::new(MoshiCodec::default()) as Arc<dyn Codec>,
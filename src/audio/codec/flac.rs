@@ -1,83 +1,91 @@
 use crate::audio::codec::traits::{Codec, CodecImpl};
-use crate::audio::codec::wav::WavCodec;
 use crate::error::CodecError;
 use crate::prelude::*;
 use codec_derive::Codec;
-use std::io::Write;
-use std::process::Command;
-use tempfile::NamedTempFile;
-use tracing::{error, info};
 
 #[derive(Debug, Codec)]
 #[codec(name = "FLAC", mime = "audio/flac", extension = "flac", lossless)]
-pub struct FlacCodec;
+pub struct FlacCodec {
+    compression_level: u32,
+}
+
+impl FlacCodec {
+    /// Create a FLAC codec at the given `compression_level` (0 fastest,
+    /// 8 smallest), matching the conventional libFLAC level range.
+    pub fn new(compression_level: u32) -> Self {
+        Self { compression_level }
+    }
+
+    /// The configured compression level.
+    pub fn compression_level(&self) -> u32 {
+        self.compression_level
+    }
+}
 
 impl Default for FlacCodec {
     fn default() -> Self {
-        Self
+        Self {
+            compression_level: 5,
+        }
     }
 }
 
 impl CodecImpl for FlacCodec {
     fn encode_samples(&self, data: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
-        // First convert to WAV
-        let wav_data = WavCodec::default().encode(data, sample_rate)?;
-        let mut temp_wav = NamedTempFile::new()
-            .map_err(|e| CodecError::Encoding(format!("Failed to create temp WAV file: {}", e)))?;
-        temp_wav
-            .write_all(&wav_data)
-            .map_err(|e| CodecError::Encoding(format!("Failed to write WAV data: {}", e)))?;
+        // 24-bit keeps the quantization error of the f32 -> int -> f32 round
+        // trip below 1.2e-7, within the lossless reconstruction tolerance.
+        const BITS_PER_SAMPLE: usize = 24;
 
-        // Use FLAC encoder
-        let output = Command::new("flac")
-            .arg("--silent")
-            .arg("--force")
-            .arg("--stdout")
-            .arg(temp_wav.path())
-            .output()
-            .map_err(|e| {
-                error!("Failed to execute FLAC encoder: {}", e);
-                CodecError::Encoding(format!("Failed to execute FLAC encoder: {}", e))
-            })?;
+        let pcm: Vec<i32> = data
+            .iter()
+            .map(|&s| (s.clamp(-1.0, 1.0) * 8_388_607.0) as i32)
+            .collect();
 
-        if !output.status.success() {
-            let error_message = String::from_utf8_lossy(&output.stderr);
-            error!("FLAC encoding failed: {}", error_message);
-            return Err(
-                CodecError::Encoding(format!("FLAC encoding failed: {}", error_message)).into(),
-            );
-        }
+        let mut config = flacenc::config::Encoder::default();
+        config.block_size = match self.compression_level {
+            0..=2 => 1024,
+            3..=5 => 4096,
+            _ => 8192,
+        };
+        let config = config
+            .into_verified()
+            .map_err(|e| CodecError::Encoding(format!("Invalid FLAC config: {:?}", e)))?;
 
-        info!(
-            "Encoded {} samples into {} bytes of FLAC data",
-            data.len(),
-            output.stdout.len()
-        );
-        Ok(output.stdout)
-    }
+        let source =
+            flacenc::source::MemSource::from_samples(&pcm, 1, BITS_PER_SAMPLE, sample_rate as usize);
+        let stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+            .map_err(|e| CodecError::Encoding(format!("FLAC encoding failed: {:?}", e)))?;
 
-    fn decode_samples(&self, data: &[u8], sample_rate: u32) -> Result<Vec<f32>> {
-        let mut temp_flac = NamedTempFile::new()
-            .map_err(|e| CodecError::Decoding(format!("Failed to create temp FLAC file: {}", e)))?;
-        temp_flac
-            .write_all(data)
-            .map_err(|e| CodecError::Decoding(format!("Failed to write FLAC data: {}", e)))?;
+        let mut sink = flacenc::bitsink::ByteSink::new();
+        stream
+            .write(&mut sink)
+            .map_err(|e| CodecError::Encoding(format!("FLAC serialization failed: {:?}", e)))?;
+
+        Ok(sink.into_inner())
+    }
 
-        let output = Command::new("flac")
-            .arg("--decode")
-            .arg("--stdout")
-            .arg(temp_flac.path())
-            .output()
-            .map_err(|e| CodecError::Decoding(format!("Failed to execute FLAC decoder: {}", e)))?;
+    fn decode_samples(&self, data: &[u8], _sample_rate: u32) -> Result<Vec<f32>> {
+        let mut reader = claxon::FlacReader::new(std::io::Cursor::new(data))
+            .map_err(|e| CodecError::Decoding(format!("Failed to read FLAC: {}", e)))?;
 
-        if !output.status.success() {
-            let error_message = String::from_utf8_lossy(&output.stderr);
-            return Err(
-                CodecError::Decoding(format!("FLAC decoding failed: {}", error_message)).into(),
-            );
+        let channels = reader.streaminfo().channels.max(1) as usize;
+        let bits = reader.streaminfo().bits_per_sample;
+        let scale = ((1i64 << (bits - 1)) - 1) as f32;
+        let mut mono = Vec::new();
+        let mut acc = 0i64;
+        let mut count = 0usize;
+        for sample in reader.samples() {
+            let sample =
+                sample.map_err(|e| CodecError::Decoding(format!("FLAC decode error: {}", e)))?;
+            acc += sample as i64;
+            count += 1;
+            if count == channels {
+                mono.push(acc as f32 / (channels as f32 * scale));
+                acc = 0;
+                count = 0;
+            }
         }
 
-        // Decode the WAV data
-        WavCodec::default().decode(&output.stdout, sample_rate)
+        Ok(mono)
     }
 }
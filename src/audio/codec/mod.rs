@@ -5,8 +5,9 @@ pub mod traits;
 // codecs
 mod flac;
 mod moshi;
+mod mp3;
 mod opus;
 mod wav;
 
 pub use factory::CODEC_FACTORY;
-pub use traits::Codec;
+pub use traits::{Codec, StreamingDecoder};
@@ -1,4 +1,5 @@
 use crate::audio::codec::traits::{Codec, CodecImpl};
+use crate::audio::resample::{resample, InterpolationMode};
 use crate::error::CodecError;
 use crate::prelude::*;
 use candle_core::{DType, Device, Tensor};
@@ -37,26 +38,11 @@ impl MoshiCodec {
         })
     }
 
+    /// Band-limited windowed-sinc resample, matching `OpusCodec`'s fixed-rate
+    /// handling — a naive linear interpolator would alias and color the
+    /// audio every time the capture rate isn't already 24kHz.
     fn resample(&self, data: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
-        if from_rate == to_rate {
-            return data.to_vec();
-        }
-
-        let ratio = to_rate as f32 / from_rate as f32;
-        let new_len = (data.len() as f32 * ratio) as usize;
-        let mut resampled = Vec::with_capacity(new_len);
-
-        for i in 0..new_len {
-            let src_idx = i as f32 / ratio;
-            let src_idx_floor = src_idx.floor() as usize;
-            let src_idx_ceil = (src_idx_floor + 1).min(data.len() - 1);
-            let frac = src_idx - src_idx.floor();
-
-            let sample = data[src_idx_floor] * (1.0 - frac) + data[src_idx_ceil] * frac;
-            resampled.push(sample);
-        }
-
-        resampled
+        resample(data, from_rate, to_rate, InterpolationMode::PolyphaseSinc)
     }
 
     fn pad_to_frame_size(&self, data: &[f32]) -> Vec<f32> {
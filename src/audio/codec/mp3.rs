@@ -0,0 +1,181 @@
+use crate::audio::codec::traits::{Codec, CodecImpl};
+use crate::error::CodecError;
+use crate::prelude::*;
+use codec_derive::Codec;
+use mp3lame_encoder::{Builder, FlushNoGap, MonoPcm};
+use tracing::info;
+
+/// LAME's encoder delay: the encoder (and a compliant decoder) prepends
+/// this many samples of priming silence to every stream, and the final
+/// frame is padded out to a full frame boundary. Without compensating for
+/// it, `decode_samples(encode_samples(x))` comes back longer than `x` and
+/// shifted, which fails length-sensitive comparisons like
+/// [`QualityMetrics::calculate`](crate::audio::codec::traits::QualityMetrics::calculate).
+const ENCODER_DELAY: usize = 529;
+
+#[derive(Debug, Codec)]
+#[codec(name = "MP3", mime = "audio/mpeg", extension = "mp3", lossy)]
+pub struct Mp3Codec {
+    bitrate: u32,
+    quality: u8,
+}
+
+impl Default for Mp3Codec {
+    fn default() -> Self {
+        Self {
+            bitrate: 128,
+            quality: 2,
+        }
+    }
+}
+
+impl Mp3Codec {
+    pub fn new(bitrate: u32) -> Self {
+        Self {
+            bitrate,
+            ..Self::default()
+        }
+    }
+
+    /// The configured bitrate in kbps, before it's mapped onto one of the
+    /// LAME-supported values by a fixed range table.
+    pub fn bitrate(&self) -> u32 {
+        self.bitrate
+    }
+
+    /// The configured LAME quality setting (0 best/slowest - 9 worst/fastest).
+    pub fn quality(&self) -> u8 {
+        self.quality
+    }
+
+    fn brate(&self) -> mp3lame_encoder::Bitrate {
+        use mp3lame_encoder::Bitrate;
+        match self.bitrate {
+            0..=40 => Bitrate::Kbps32,
+            41..=56 => Bitrate::Kbps48,
+            57..=72 => Bitrate::Kbps64,
+            73..=104 => Bitrate::Kbps96,
+            105..=144 => Bitrate::Kbps128,
+            145..=208 => Bitrate::Kbps192,
+            209..=288 => Bitrate::Kbps256,
+            _ => Bitrate::Kbps320,
+        }
+    }
+
+    fn lame_quality(&self) -> mp3lame_encoder::Quality {
+        use mp3lame_encoder::Quality;
+        match self.quality {
+            0 => Quality::Best,
+            1 => Quality::SecondBest,
+            2 => Quality::NearBest,
+            3..=6 => Quality::Good,
+            7 => Quality::Ok,
+            8 => Quality::SecondWorst,
+            _ => Quality::Worst,
+        }
+    }
+}
+
+impl CodecImpl for Mp3Codec {
+    fn encode_samples(&self, data: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
+        let mut builder = Builder::new()
+            .ok_or_else(|| CodecError::Encoding("Failed to create LAME builder".into()))?;
+        builder
+            .set_num_channels(1)
+            .map_err(|e| CodecError::Encoding(format!("Failed to set channels: {}", e)))?;
+        builder
+            .set_sample_rate(sample_rate)
+            .map_err(|e| CodecError::Encoding(format!("Failed to set sample rate: {}", e)))?;
+        builder
+            .set_brate(self.brate())
+            .map_err(|e| CodecError::Encoding(format!("Failed to set bitrate: {}", e)))?;
+        builder
+            .set_quality(self.lame_quality())
+            .map_err(|e| CodecError::Encoding(format!("Failed to set quality: {}", e)))?;
+
+        let mut encoder = builder
+            .build()
+            .map_err(|e| CodecError::Encoding(format!("Failed to build LAME encoder: {}", e)))?;
+
+        // LAME consumes interleaved i16 PCM; downmix is already mono here.
+        let pcm: Vec<i16> = data
+            .iter()
+            .map(|&s| (s.clamp(-1.0, 1.0) * 32767.0) as i16)
+            .collect();
+
+        let mut buffer = Vec::with_capacity(mp3lame_encoder::max_required_buffer_size(pcm.len()));
+        let written = encoder
+            .encode(MonoPcm(&pcm), buffer.spare_capacity_mut())
+            .map_err(|e| CodecError::Encoding(format!("MP3 encoding failed: {}", e)))?;
+        unsafe {
+            buffer.set_len(buffer.len() + written);
+        }
+
+        // Flush LAME's final frames so the stream is complete.
+        let written = encoder
+            .flush::<FlushNoGap>(buffer.spare_capacity_mut())
+            .map_err(|e| CodecError::Encoding(format!("MP3 flush failed: {}", e)))?;
+        unsafe {
+            buffer.set_len(buffer.len() + written);
+        }
+
+        info!(
+            "Encoded {} samples into {} bytes of MP3 data",
+            data.len(),
+            buffer.len()
+        );
+
+        // Prefix with the original sample count so `decode_samples` can trim
+        // LAME's encoder delay and frame padding back off on the way out.
+        let mut output = Vec::with_capacity(4 + buffer.len());
+        output.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        output.extend(buffer);
+        Ok(output)
+    }
+
+    fn decode_samples(&self, data: &[u8], _sample_rate: u32) -> Result<Vec<f32>> {
+        if data.len() < 4 {
+            return Err(CodecError::Decoding("MP3 data missing length header".into()).into());
+        }
+        let original_len = u32::from_le_bytes(data[0..4].try_into()?) as usize;
+
+        let mut decoder = minimp3::Decoder::new(&data[4..]);
+        let mut samples = Vec::new();
+
+        loop {
+            match decoder.next_frame() {
+                Ok(minimp3::Frame {
+                    data,
+                    channels,
+                    ..
+                }) => {
+                    if channels <= 1 {
+                        samples.extend(data.iter().map(|&s| s as f32 / 32768.0));
+                    } else {
+                        // Downmix interleaved channels to mono by averaging.
+                        for frame in data.chunks_exact(channels) {
+                            let sum: i32 = frame.iter().map(|&s| s as i32).sum();
+                            samples.push(sum as f32 / (channels as f32 * 32768.0));
+                        }
+                    }
+                }
+                Err(minimp3::Error::Eof) => break,
+                Err(e) => {
+                    return Err(CodecError::Decoding(format!("MP3 decoding failed: {}", e)).into())
+                }
+            }
+        }
+
+        // Drop the encoder's priming delay, then trim/zero-pad to the
+        // original length so round-tripped audio lines up sample-for-sample
+        // with the input.
+        if samples.len() > ENCODER_DELAY {
+            samples.drain(0..ENCODER_DELAY);
+        } else {
+            samples.clear();
+        }
+        samples.resize(original_len, 0.0);
+
+        Ok(samples)
+    }
+}
@@ -1,5 +1,6 @@
 use crate::audio::codec::traits::{Codec, CodecImpl};
 use crate::audio::codec::wav::WavCodec;
+use crate::audio::resample::Resampler;
 use crate::error::CodecError;
 use crate::prelude::*;
 use codec_derive::Codec;
@@ -8,6 +9,10 @@ use std::process::Command;
 use tempfile::NamedTempFile;
 use tracing::{error, info};
 
+/// Half-width of the windowed-sinc kernel used to convert between the
+/// capture rate and Opus's native 48kHz.
+const RESAMPLE_TAPS: isize = 16;
+
 #[derive(Debug, Codec)]
 #[codec(name = "OPUS", mime = "audio/opus", extension = "opus", lossy)]
 pub struct OpusCodec {
@@ -32,26 +37,47 @@ impl OpusCodec {
         }
     }
 
+    /// Resample `data` from `input_rate` to `self.sample_rate` with a
+    /// band-limited windowed-sinc kernel, avoiding the aliasing a plain
+    /// linear interpolator would introduce.
     fn resample(&self, data: &[f32], input_rate: u32) -> Vec<f32> {
-        if input_rate == self.sample_rate {
-            return data.to_vec();
-        }
-
-        let ratio = self.sample_rate as f32 / input_rate as f32;
-        let new_len = (data.len() as f32 * ratio) as usize;
-        let mut resampled = Vec::with_capacity(new_len);
-
-        for i in 0..new_len {
-            let src_idx = i as f32 / ratio;
-            let src_idx_floor = src_idx.floor() as usize;
-            let src_idx_ceil = (src_idx_floor + 1).min(data.len() - 1);
-            let frac = src_idx - src_idx.floor();
+        Resampler::new(input_rate, self.sample_rate, RESAMPLE_TAPS).process(data)
+    }
 
-            let sample = data[src_idx_floor] * (1.0 - frac) + data[src_idx_ceil] * frac;
-            resampled.push(sample);
+    /// Decode an Ogg Opus buffer directly: demux pages with `ogg` and feed
+    /// each audio packet to a libopus decoder, with no temp file or
+    /// subprocess involved. The two leading header packets (`OpusHead`,
+    /// `OpusTags`) carry no audio and are skipped.
+    fn decode_native(&self, data: &[u8], output_rate: u32) -> Result<Vec<f32>> {
+        use audiopus::coder::Decoder as OpusDecoder;
+        use audiopus::{Channels, SampleRate};
+        use ogg::reading::PacketReader;
+
+        let mut opus_decoder = OpusDecoder::new(SampleRate::Hz48000, Channels::Mono)
+            .map_err(|e| CodecError::Decoding(format!("Failed to create Opus decoder: {}", e)))?;
+
+        let mut reader = PacketReader::new(std::io::Cursor::new(data));
+        let mut samples = Vec::new();
+        // Largest frame libopus can produce at 48kHz (120ms).
+        let mut pcm = [0i16; 5760];
+
+        while let Some(packet) = reader
+            .read_packet()
+            .map_err(|e| CodecError::Decoding(format!("Failed to read Ogg page: {}", e)))?
+        {
+            if packet.data.starts_with(b"OpusHead") || packet.data.starts_with(b"OpusTags") {
+                continue;
+            }
+
+            let written = opus_decoder
+                .decode(Some(&packet.data), &mut pcm[..], false)
+                .map_err(|e| CodecError::Decoding(format!("Opus decode failed: {}", e)))?;
+            samples.extend(pcm[..written].iter().map(|&s| s as f32 / 32768.0));
         }
 
-        resampled
+        let resampled = self.resample(&samples, output_rate);
+        info!("Decoded Opus data to {} samples", resampled.len());
+        Ok(resampled)
     }
 }
 
@@ -101,6 +127,12 @@ impl CodecImpl for OpusCodec {
         Ok(output.stdout)
     }
 
+    #[cfg(not(feature = "ffmpeg-fallback"))]
+    fn decode_samples(&self, data: &[u8], output_rate: u32) -> Result<Vec<f32>> {
+        self.decode_native(data, output_rate)
+    }
+
+    #[cfg(feature = "ffmpeg-fallback")]
     fn decode_samples(&self, data: &[u8], output_rate: u32) -> Result<Vec<f32>> {
         // Create temporary Opus file
         let mut temp_opus = NamedTempFile::new()
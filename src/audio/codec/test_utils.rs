@@ -1,3 +1,4 @@
+use crate::audio::resample::{resample, InterpolationMode};
 use crate::error::Error;
 use crate::prelude::*;
 use cpal::Sample;
@@ -12,6 +13,10 @@ pub struct AudioTestCase {
     pub sample_rate: u32,
     pub duration: Duration,
     pub category: AudioCategory,
+    /// Optional path to dump this case's encoded output to, so a developer
+    /// can verify it with an external decoder before pinning its golden
+    /// digest computed by [`encoded_digest`].
+    pub output_file: Option<&'static str>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -43,27 +48,11 @@ pub struct TestSignal {
 }
 
 impl TestSignal {
+    /// Band-limited windowed-sinc resample to `new_rate`, so sine-sweep and
+    /// multitone test cases aren't scored against aliasing/spectral tilt
+    /// this step itself introduced.
     pub fn resample(&self, new_rate: u32) -> Vec<f32> {
-        if self.sample_rate == new_rate {
-            return self.samples.clone();
-        }
-
-        let ratio = new_rate as f32 / self.sample_rate as f32;
-        let new_len = (self.samples.len() as f32 * ratio) as usize;
-        let mut resampled = Vec::with_capacity(new_len);
-
-        for i in 0..new_len {
-            let src_idx = i as f32 / ratio;
-            let src_idx_floor = src_idx.floor() as usize;
-            let src_idx_ceil = (src_idx_floor + 1).min(self.samples.len() - 1);
-            let frac = src_idx - src_idx.floor();
-
-            let sample =
-                self.samples[src_idx_floor] * (1.0 - frac) + self.samples[src_idx_ceil] * frac;
-            resampled.push(sample);
-        }
-
-        resampled
+        resample(&self.samples, self.sample_rate, new_rate, InterpolationMode::PolyphaseSinc)
     }
 }
 
@@ -127,6 +116,7 @@ impl AudioTestSuite {
                     sample_len as f32 / sample_rate as f32,
                 ),
                 category: AudioCategory::Speech,
+                output_file: None,
             });
         } else {
             tracing::warn!("Could not find test_voice.wav in data directory");
@@ -135,9 +125,12 @@ impl AudioTestSuite {
         Ok(suite)
     }
 
+    /// Seeded so the white-noise case (and any golden digest computed over
+    /// it) is reproducible across runs instead of drawing from the thread's
+    /// unseeded RNG.
     fn generate_white_noise(sample_rate: u32, duration: f32, name: &str) -> Result<AudioTestCase> {
         let num_samples = (sample_rate as f32 * duration) as usize;
-        let mut rng = rand::thread_rng();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0xA11CE);
         let samples: Vec<f32> = (0..num_samples)
             .map(|_| rng.gen_range(-1.0..=1.0))
             .collect();
@@ -148,6 +141,7 @@ impl AudioTestSuite {
             sample_rate,
             duration: Duration::from_secs_f32(duration),
             category: AudioCategory::Noise,
+            output_file: None,
         })
     }
 
@@ -176,6 +170,7 @@ impl AudioTestSuite {
             sample_rate,
             duration: Duration::from_secs_f32(duration),
             category: AudioCategory::Noise,
+            output_file: None,
         })
     }
 
@@ -196,6 +191,7 @@ impl AudioTestSuite {
             sample_rate,
             duration: Duration::from_secs_f32(duration),
             category: AudioCategory::Synthetic,
+            output_file: None,
         })
     }
 
@@ -223,6 +219,7 @@ impl AudioTestSuite {
             sample_rate,
             duration: Duration::from_secs_f32(duration),
             category: AudioCategory::Synthetic,
+            output_file: None,
         })
     }
 
@@ -255,10 +252,28 @@ impl AudioTestSuite {
             sample_rate: spec.sample_rate,
             duration,
             category,
+            output_file: None,
         })
     }
 }
 
+/// Hash `encoded` (plus `decoded`, if given, reinterpreted as little-endian
+/// bytes) with SHA-256, for golden-digest regression tests: a codec that
+/// starts producing bit-different output for the same input fails this even
+/// when its SNR/speed assertions still pass.
+pub fn encoded_digest(encoded: &[u8], decoded: Option<&[f32]>) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    hasher.update(encoded);
+    if let Some(decoded) = decoded {
+        for sample in decoded {
+            hasher.update(sample.to_le_bytes());
+        }
+    }
+    format!("{:x}", hasher.finalize())
+}
+
 // Quality metrics for audio comparison
 #[derive(Debug)]
 pub struct AudioQualityMetrics {
@@ -266,10 +281,32 @@ pub struct AudioQualityMetrics {
     pub mse: f32,
     pub max_abs_error: f32,
     pub correlation: f32,
+    /// RMS, over overlapping frames, of the per-bin dB difference between
+    /// the original and decoded magnitude spectra. Unlike time-domain SNR
+    /// and correlation, a small phase shift a lossy codec's perceptual model
+    /// doesn't penalize won't tank this metric, so it ranks lossy codecs
+    /// closer to how they'd actually sound.
+    pub log_spectral_distance: f32,
+    /// Mean of the per-frame SNR (dB), each clamped to `[-10, 35]` so a
+    /// handful of near-silent frames don't dominate the average.
+    pub seg_snr: f32,
+    /// Per-octave-band gain error (dB) between the original and decoded
+    /// spectra, so a regression in a specific band (e.g. a lossy codec
+    /// rolling off highs) shows up directly instead of being averaged away
+    /// by a single wideband SNR number.
+    pub frequency_response: FrequencyResponse,
+}
+
+/// Octave-band gain error: `relative_power[i]` is the decoded-vs-original
+/// power ratio (dB) of the band centered at `bands[i]`.
+#[derive(Debug, Clone)]
+pub struct FrequencyResponse {
+    pub bands: Vec<f32>,
+    pub relative_power: Vec<f32>,
 }
 
 impl AudioQualityMetrics {
-    pub fn calculate(original: &[f32], decoded: &[f32]) -> Self {
+    pub fn calculate(original: &[f32], decoded: &[f32], sample_rate: u32) -> Self {
         let len = original.len().min(decoded.len());
         let orig = &original[..len];
         let dec = &decoded[..len];
@@ -318,11 +355,176 @@ impl AudioQualityMetrics {
             0.0
         };
 
+        let (log_spectral_distance, seg_snr) = spectral_metrics(orig, dec);
+        let frequency_response = analyze_frequency_response(orig, dec, sample_rate);
+
         Self {
             snr,
             mse,
             max_abs_error: max_error,
             correlation,
+            log_spectral_distance,
+            seg_snr,
+            frequency_response,
+        }
+    }
+}
+
+/// Per-octave-band gain error in dB (`10*log10(decoded_power / original_power)`)
+/// across the 125Hz-16kHz speech/music range, directly usable for the
+/// spectrogram visualization's octave-band layout.
+fn analyze_frequency_response(
+    original: &[f32],
+    decoded: &[f32],
+    sample_rate: u32,
+) -> FrequencyResponse {
+    let bands = vec![125.0, 250.0, 500.0, 1000.0, 2000.0, 4000.0, 8000.0, 16000.0];
+
+    let original_power = band_power(original, sample_rate, &bands);
+    let decoded_power = band_power(decoded, sample_rate, &bands);
+
+    let relative_power = original_power
+        .iter()
+        .zip(decoded_power.iter())
+        .map(|(&orig, &dec)| 10.0 * (dec / orig.max(1e-12)).log10())
+        .collect();
+
+    FrequencyResponse {
+        bands,
+        relative_power,
+    }
+}
+
+/// Sum the magnitude-spectrum power of each octave band in `bands`, where a
+/// band centered at `f` owns the octave `[f/sqrt(2), f*sqrt(2))`. The
+/// signal is Hann-windowed before the forward FFT to limit spectral leakage.
+fn band_power(signal: &[f32], sample_rate: u32, bands: &[f32]) -> Vec<f32> {
+    use rustfft::{num_complex::Complex, FftPlanner};
+
+    let n = signal.len();
+    if n == 0 {
+        return vec![0.0; bands.len()];
+    }
+
+    let mut buffer: Vec<Complex<f32>> = signal
+        .iter()
+        .enumerate()
+        .map(|(i, &s)| {
+            let w = 0.5
+                - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (n as f32 - 1.0).max(1.0)).cos();
+            Complex::new(s * w, 0.0)
+        })
+        .collect();
+
+    let mut planner = FftPlanner::new();
+    planner.plan_fft_forward(n).process(&mut buffer);
+
+    let mut power = vec![0.0f32; bands.len()];
+    // Only the first half of the spectrum carries distinct information for a
+    // real input.
+    for (bin, value) in buffer.iter().enumerate().take(n / 2 + 1) {
+        let freq = bin as f32 * sample_rate as f32 / n as f32;
+        for (i, &center) in bands.iter().enumerate() {
+            let lower = center / std::f32::consts::SQRT_2;
+            let upper = center * std::f32::consts::SQRT_2;
+            if freq >= lower && freq < upper {
+                power[i] += value.norm_sqr();
+            }
         }
     }
+
+    power
+}
+
+/// `(log_spectral_distance, seg_snr)` over 50%-overlapping, Hann-windowed
+/// 1024-sample frames.
+///
+/// Log-spectral distance is `sqrt(mean_over_frames(mean_over_bins((10*log10(P_orig/P_dec))^2)))`,
+/// with a power floor so a silent bin doesn't take `log10(0)`. Segmental SNR
+/// is the mean of each frame's time-domain SNR, clamped to `[-10, 35]` dB.
+fn spectral_metrics(original: &[f32], decoded: &[f32]) -> (f32, f32) {
+    use rustfft::{num_complex::Complex, FftPlanner};
+
+    const FRAME_SIZE: usize = 1024;
+    const HOP: usize = FRAME_SIZE / 2;
+    const POWER_FLOOR: f32 = 1e-10;
+
+    let len = original.len().min(decoded.len());
+    if len < FRAME_SIZE {
+        return (0.0, 0.0);
+    }
+
+    let window: Vec<f32> = (0..FRAME_SIZE)
+        .map(|i| {
+            0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (FRAME_SIZE as f32 - 1.0)).cos()
+        })
+        .collect();
+
+    let mut planner = FftPlanner::new();
+    let fft = planner.plan_fft_forward(FRAME_SIZE);
+
+    let frame_power = |frame: &[f32]| -> Vec<f32> {
+        let mut buffer: Vec<Complex<f32>> = frame
+            .iter()
+            .zip(window.iter())
+            .map(|(&s, &w)| Complex::new(s * w, 0.0))
+            .collect();
+        fft.process(&mut buffer);
+        buffer
+            .iter()
+            .take(FRAME_SIZE / 2 + 1)
+            .map(|c| c.norm_sqr())
+            .collect()
+    };
+
+    let mut lsd_sum = 0.0f32;
+    let mut seg_snr_sum = 0.0f32;
+    let mut frame_count = 0usize;
+    let mut start = 0;
+
+    while start + FRAME_SIZE <= len {
+        let orig_frame = &original[start..start + FRAME_SIZE];
+        let dec_frame = &decoded[start..start + FRAME_SIZE];
+
+        let orig_power = frame_power(orig_frame);
+        let dec_power = frame_power(dec_frame);
+
+        let frame_lsd_sq: f32 = orig_power
+            .iter()
+            .zip(dec_power.iter())
+            .map(|(&o, &d)| {
+                let diff_db = 10.0 * (o.max(POWER_FLOOR) / d.max(POWER_FLOOR)).log10();
+                diff_db * diff_db
+            })
+            .sum::<f32>()
+            / orig_power.len() as f32;
+        lsd_sum += frame_lsd_sq;
+
+        let signal_power =
+            orig_frame.iter().map(|x| x.powi(2)).sum::<f32>() / FRAME_SIZE as f32;
+        let noise_power = orig_frame
+            .iter()
+            .zip(dec_frame.iter())
+            .map(|(&o, &d)| (o - d).powi(2))
+            .sum::<f32>()
+            / FRAME_SIZE as f32;
+        let frame_snr = if noise_power > 0.0 {
+            (10.0 * (signal_power / noise_power).log10()).clamp(-10.0, 35.0)
+        } else {
+            35.0
+        };
+        seg_snr_sum += frame_snr;
+
+        frame_count += 1;
+        start += HOP;
+    }
+
+    if frame_count == 0 {
+        return (0.0, 0.0);
+    }
+
+    (
+        (lsd_sum / frame_count as f32).sqrt(),
+        seg_snr_sum / frame_count as f32,
+    )
 }
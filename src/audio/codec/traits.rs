@@ -1,3 +1,4 @@
+use crate::audio::buffer::ms_to_samples;
 use crate::error::{AudioError, CodecError};
 use crate::prelude::*;
 use std::fmt::Debug;
@@ -6,6 +7,68 @@ use std::time::{Duration, Instant};
 pub trait CodecImpl: Send + Sync + Debug {
     fn encode_samples(&self, data: &[f32], sample_rate: u32) -> Result<Vec<u8>>;
     fn decode_samples(&self, data: &[u8], sample_rate: u32) -> Result<Vec<f32>>;
+
+    /// Build a fresh incremental decoder seeded at `sample_rate`, for
+    /// callers that want to start playback/streaming before the whole
+    /// encoded segment has arrived.
+    ///
+    /// The default wraps the one-shot `decode_samples`, so it only works for
+    /// codecs that don't implement true streaming: codecs that can parse
+    /// their container incrementally (Ogg pages, MP3 frames) should override
+    /// this with a real `StreamingDecoder`.
+    fn streaming_decoder(&self, sample_rate: u32) -> Box<dyn StreamingDecoder>
+    where
+        Self: Sized + Clone + 'static,
+    {
+        Box::new(OneShotStreamingDecoder {
+            codec: self.clone(),
+            sample_rate,
+            buffered: Vec::new(),
+            samples_returned: 0,
+        })
+    }
+}
+
+/// Incrementally decode bytes arriving in blocks (e.g. as Ogg/MP3 pages are
+/// streamed in), pulling out PCM samples as they become available instead of
+/// waiting for the whole encoded buffer.
+pub trait StreamingDecoder: Send {
+    /// Feed newly-arrived encoded bytes into the decoder.
+    fn push_bytes(&mut self, bytes: &[u8]);
+
+    /// Drain and return any samples that became decodable since the last
+    /// call. Returns an empty `Vec` if nothing new is ready yet.
+    fn pull_samples(&mut self) -> Result<Vec<f32>>;
+}
+
+/// Fallback `StreamingDecoder` for codecs without true incremental decoding:
+/// re-runs `decode_samples` over everything buffered so far on every pull,
+/// swallowing errors from an as-yet-incomplete buffer, and only returns the
+/// tail that hasn't been handed out yet.
+struct OneShotStreamingDecoder<C> {
+    codec: C,
+    sample_rate: u32,
+    buffered: Vec<u8>,
+    samples_returned: usize,
+}
+
+impl<C: CodecImpl> StreamingDecoder for OneShotStreamingDecoder<C> {
+    fn push_bytes(&mut self, bytes: &[u8]) {
+        self.buffered.extend_from_slice(bytes);
+    }
+
+    fn pull_samples(&mut self) -> Result<Vec<f32>> {
+        let samples = match self.codec.decode_samples(&self.buffered, self.sample_rate) {
+            Ok(samples) => samples,
+            // Not enough bytes yet to form a valid stream; try again on the
+            // next push.
+            Err(_) => return Ok(Vec::new()),
+        };
+
+        let new_samples = samples[self.samples_returned.min(samples.len())..].to_vec();
+        self.samples_returned = samples.len();
+        Ok(new_samples)
+    }
 }
 
 pub trait Codec: CodecImpl {
@@ -24,6 +87,31 @@ pub trait Codec: CodecImpl {
         self.decode_samples(data, sample_rate)
     }
 
+    /// Decode only the samples covering `[start, end)` of a stored clip.
+    ///
+    /// The default decodes everything and slices by sample index, converting
+    /// offsets with [`ms_to_samples`] (`round(ms * sample_rate / 1000)`), the
+    /// same conversion `AudioBuffer::read_range_ms` and storage retrieval use,
+    /// so they agree on what a given offset means. Codecs with true
+    /// frame-level seeking (MP3/FLAC) should override this to skip to the
+    /// nearest frame boundary and decode only the needed region.
+    fn decode_range(
+        &self,
+        data: &[u8],
+        sample_rate: u32,
+        start: Duration,
+        end: Duration,
+    ) -> Result<Vec<f32>> {
+        let samples = self.decode(data, sample_rate)?;
+        let start_sample = ms_to_samples(start.as_millis() as u64, sample_rate).min(samples.len());
+        let end_sample = ms_to_samples(end.as_millis() as u64, sample_rate).min(samples.len());
+
+        if start_sample >= end_sample {
+            return Ok(Vec::new());
+        }
+        Ok(samples[start_sample..end_sample].to_vec())
+    }
+
     fn compression_ratio(&self, data: &[f32], sample_rate: u32) -> Result<f32> {
         let encoded = self.encode(data, sample_rate)?;
         Ok(data.len() as f32 * std::mem::size_of::<f32>() as f32 / encoded.len() as f32)
@@ -61,8 +61,13 @@ impl CodecImpl for WavCodec {
         Ok(buffer)
     }
 
+    /// Parses the `fmt ` chunk for the format tag/channel count/bit depth
+    /// actually present in `data` rather than trusting `self.bits_per_sample`,
+    /// so this can decode WAV files this codec didn't itself produce (e.g.
+    /// stereo, 24-bit, or IEEE-float captures), downmixing multi-channel
+    /// audio to the mono `Vec<f32>` the rest of the pipeline expects.
     fn decode_samples(&self, data: &[u8], _sample_rate: u32) -> Result<Vec<f32>> {
-        if data.len() < 44 {
+        if data.len() < 12 {
             return Err(Error::Audio(AudioError::Codec(CodecError::InvalidData(
                 "WAV header too short",
             ))));
@@ -74,39 +79,30 @@ impl CodecImpl for WavCodec {
             ))));
         }
 
+        let mut fmt: Option<WavFormat> = None;
         let mut offset = 12;
+
         while offset + 8 <= data.len() {
             let chunk_id = &data[offset..offset + 4];
             let chunk_size = u32::from_le_bytes(
                 data[offset + 4..offset + 8]
                     .try_into()
                     .map_err(|_| CodecError::InvalidData("Invalid chunk size"))?,
-            );
-
-            if chunk_id == b"data" {
-                let data_offset = offset + 8;
-                let bytes_per_sample = self.bits_per_sample as usize / 8;
-                let mut samples = Vec::new();
-
-                for chunk in data[data_offset..].chunks_exact(bytes_per_sample) {
-                    let sample = match self.bits_per_sample {
-                        16 => {
-                            let value = i16::from_le_bytes(chunk.try_into()?);
-                            value as f32 / 32767.0
-                        }
-                        32 => {
-                            let value = i32::from_le_bytes(chunk.try_into()?);
-                            value as f32 / 2147483647.0
-                        }
-                        _ => unreachable!(),
-                    };
-                    samples.push(sample);
-                }
-
-                return Ok(samples);
+            ) as usize;
+            let chunk_start = offset + 8;
+            let chunk_end = (chunk_start + chunk_size).min(data.len());
+
+            if chunk_id == b"fmt " {
+                fmt = Some(WavFormat::parse(&data[chunk_start..chunk_end])?);
+            } else if chunk_id == b"data" {
+                let fmt = fmt
+                    .as_ref()
+                    .ok_or_else(|| CodecError::InvalidData("data chunk before fmt chunk"))?;
+                return fmt.decode(&data[chunk_start..chunk_end]);
             }
+            // Unknown chunks (LIST/fact/etc.) are skipped by their declared size.
 
-            offset += 8 + chunk_size as usize;
+            offset = chunk_start + chunk_size + (chunk_size % 2);
         }
 
         Err(Error::Audio(AudioError::Codec(CodecError::InvalidData(
@@ -115,6 +111,78 @@ impl CodecImpl for WavCodec {
     }
 }
 
+/// WAV format tags this codec recognizes in a `fmt ` chunk, per the
+/// Microsoft WAVEFORMATEX `wFormatTag` field.
+const WAVE_FORMAT_PCM: u16 = 1;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+
+/// The subset of a parsed `fmt ` chunk needed to decode `data` correctly,
+/// independent of what this codec instance was constructed with.
+struct WavFormat {
+    audio_format: u16,
+    num_channels: u16,
+    bits_per_sample: u16,
+}
+
+impl WavFormat {
+    fn parse(chunk: &[u8]) -> Result<Self> {
+        if chunk.len() < 16 {
+            return Err(Error::Audio(AudioError::Codec(CodecError::InvalidData(
+                "fmt chunk too short",
+            ))));
+        }
+
+        Ok(Self {
+            audio_format: u16::from_le_bytes(chunk[0..2].try_into()?),
+            num_channels: u16::from_le_bytes(chunk[2..4].try_into()?),
+            bits_per_sample: u16::from_le_bytes(chunk[14..16].try_into()?),
+        })
+    }
+
+    /// Decode interleaved PCM/float samples and downmix to mono by
+    /// averaging channels.
+    fn decode(&self, data: &[u8]) -> Result<Vec<f32>> {
+        let channels = self.num_channels.max(1) as usize;
+        let bytes_per_sample = self.bits_per_sample as usize / 8;
+        let frame_size = bytes_per_sample * channels;
+        if frame_size == 0 {
+            return Err(Error::Audio(AudioError::Codec(CodecError::InvalidData(
+                "Invalid fmt chunk: zero-size frame",
+            ))));
+        }
+
+        let decode_sample = |bytes: &[u8]| -> Result<f32> {
+            Ok(match (self.audio_format, self.bits_per_sample) {
+                (WAVE_FORMAT_PCM, 16) => i16::from_le_bytes(bytes.try_into()?) as f32 / 32767.0,
+                (WAVE_FORMAT_PCM, 24) => {
+                    let value = i32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0])
+                        << 8
+                        >> 8; // sign-extend the 24-bit value
+                    value as f32 / 8_388_607.0
+                }
+                (WAVE_FORMAT_PCM, 32) => i32::from_le_bytes(bytes.try_into()?) as f32 / 2_147_483_647.0,
+                (WAVE_FORMAT_IEEE_FLOAT, 32) => f32::from_le_bytes(bytes.try_into()?),
+                _ => {
+                    return Err(CodecError::InvalidData(
+                        "Unsupported WAV format tag/bit-depth combination",
+                    )
+                    .into())
+                }
+            })
+        };
+
+        data.chunks_exact(frame_size)
+            .map(|frame| -> Result<f32> {
+                let mut sum = 0.0f32;
+                for ch in frame.chunks_exact(bytes_per_sample) {
+                    sum += decode_sample(ch)?;
+                }
+                Ok(sum / channels as f32)
+            })
+            .collect()
+    }
+}
+
 impl WavCodec {
     pub fn new(bits_per_sample: u16) -> Result<Self> {
         match bits_per_sample {
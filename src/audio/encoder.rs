@@ -5,16 +5,89 @@ use moshi::encodec::{Config, Encodec};
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::io::Write;
-use std::process::{Command, Stdio};
 use std::sync::{Arc, Mutex};
-use tempfile::NamedTempFile;
-use tracing::{error, info};
+use tracing::info;
+
+/// Fixed Ogg bitstream serial for the single Opus logical stream we emit.
+const OGG_SERIAL: u32 = 0x4f_50_55_53;
 
 pub trait AudioEncoder: Send + Sync {
     fn encode(&self, data: &[f32], sample_rate: u32) -> Result<Vec<u8>>;
     fn decode(&self, data: &[u8], sample_rate: u32) -> Result<Vec<f32>>;
     fn mime_type(&self) -> &'static str;
     fn content_disposition(&self) -> &'static str;
+
+    /// The fixed sample rate this encoder requires, if any. When set, callers
+    /// must resample the buffer to this rate before calling [`encode`](Self::encode).
+    /// Encoders that accept any rate (WAV/PCM/MP3) return `None`.
+    fn required_sample_rate(&self) -> Option<u32> {
+        None
+    }
+}
+
+/// Resample `input` from `from` to `to` using a Hann-windowed sinc FIR.
+///
+/// Each output sample maps to input position `n * from / to`, a low-pass
+/// kernel is generated with its cutoff at the smaller of the two Nyquist
+/// frequencies, and each
+/// output sample is produced by convolving that kernel against the neighbouring
+/// input samples (zero-padded at the edges).
+pub fn resample(input: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || input.is_empty() {
+        return input.to_vec();
+    }
+
+    // Kernel half-width in input taps.
+    const HALF: isize = 24;
+
+    let new_len = (input.len() as f64 * to as f64 / from as f64).round() as usize;
+    let cutoff = (to as f64 / from as f64).min(1.0); // relative to input Nyquist
+    let step = from as f64 / to as f64;
+
+    let mut out = Vec::with_capacity(new_len);
+    for n in 0..new_len {
+        let center = n as f64 * step;
+        let base = center.floor() as isize;
+
+        let mut acc = 0.0f64;
+        let mut norm = 0.0f64;
+        for k in (base - HALF)..=(base + HALF) {
+            let x = k as f64 - center;
+            let w = hann(x / HALF as f64);
+            if w == 0.0 {
+                continue;
+            }
+            let tap = cutoff * sinc(cutoff * x) * w;
+            norm += tap;
+            if k >= 0 && (k as usize) < input.len() {
+                acc += input[k as usize] as f64 * tap;
+            }
+        }
+        out.push(if norm.abs() > f64::EPSILON {
+            (acc / norm) as f32
+        } else {
+            0.0
+        });
+    }
+
+    out
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        1.0
+    } else {
+        let pix = std::f64::consts::PI * x;
+        pix.sin() / pix
+    }
+}
+
+fn hann(t: f64) -> f64 {
+    if t.abs() >= 1.0 {
+        0.0
+    } else {
+        0.5 * (1.0 + (std::f64::consts::PI * t).cos())
+    }
 }
 
 pub struct PcmEncoder;
@@ -29,7 +102,16 @@ impl AudioEncoder for PcmEncoder {
     }
 
     fn decode(&self, data: &[u8], _sample_rate: u32) -> Result<Vec<f32>> {
-        todo!()
+        // Raw little-endian 32-bit float PCM: the inverse of `encode`.
+        if data.len() % 4 != 0 {
+            return Err(
+                CodecError::Decoding("PCM data length is not a multiple of 4".into()).into(),
+            );
+        }
+        Ok(data
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect())
     }
 
     fn mime_type(&self) -> &'static str {
@@ -41,6 +123,84 @@ impl AudioEncoder for PcmEncoder {
     }
 }
 
+/// Decode a RIFF/WAVE byte stream into the canonical mono `Vec<f32>` the crate
+/// uses. Supports 16/24/32-bit integer and 32-bit float PCM, downmixing any
+/// channel count to mono by averaging, and skips unknown chunks.
+fn decode_wav(data: &[u8]) -> Result<Vec<f32>> {
+    if data.len() < 44 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return Err(CodecError::Decoding("Invalid WAV header".into()).into());
+    }
+
+    let mut audio_format = 1u16;
+    let mut num_channels = 1u16;
+    let mut bits_per_sample = 16u16;
+    let mut offset = 12;
+
+    while offset + 8 <= data.len() {
+        let chunk_id = &data[offset..offset + 4];
+        let chunk_size =
+            u32::from_le_bytes(data[offset + 4..offset + 8].try_into()?) as usize;
+        let body = offset + 8;
+
+        if chunk_id == b"fmt " && body + 16 <= data.len() {
+            audio_format = u16::from_le_bytes(data[body..body + 2].try_into()?);
+            num_channels = u16::from_le_bytes(data[body + 2..body + 4].try_into()?);
+            bits_per_sample = u16::from_le_bytes(data[body + 14..body + 16].try_into()?);
+        } else if chunk_id == b"data" {
+            let end = (body + chunk_size).min(data.len());
+            return deinterleave_to_mono(
+                &data[body..end],
+                audio_format,
+                num_channels.max(1),
+                bits_per_sample,
+            );
+        }
+
+        // Chunks are word-aligned; advance past the padding byte if present.
+        offset = body + chunk_size + (chunk_size & 1);
+    }
+
+    Err(CodecError::Decoding("No data chunk found".into()).into())
+}
+
+fn deinterleave_to_mono(
+    data: &[u8],
+    audio_format: u16,
+    channels: u16,
+    bits_per_sample: u16,
+) -> Result<Vec<f32>> {
+    let channels = channels as usize;
+    let bytes = (bits_per_sample / 8) as usize;
+    if bytes == 0 {
+        return Err(CodecError::Decoding("Invalid bits per sample".into()).into());
+    }
+
+    let sample = |chunk: &[u8]| -> f32 {
+        match (audio_format, bits_per_sample) {
+            (3, 32) => f32::from_le_bytes(chunk.try_into().unwrap()),
+            (_, 16) => i16::from_le_bytes(chunk.try_into().unwrap()) as f32 / 32768.0,
+            (_, 24) => {
+                let v = (chunk[0] as i32) | ((chunk[1] as i32) << 8) | ((chunk[2] as i32) << 16);
+                let v = (v << 8) >> 8; // sign-extend 24-bit
+                v as f32 / 8_388_608.0
+            }
+            (_, 32) => i32::from_le_bytes(chunk.try_into().unwrap()) as f32 / 2_147_483_648.0,
+            _ => 0.0,
+        }
+    };
+
+    let frame = bytes * channels;
+    let mut mono = Vec::with_capacity(data.len() / frame.max(1));
+    for frame_bytes in data.chunks_exact(frame) {
+        let sum: f32 = frame_bytes
+            .chunks_exact(bytes)
+            .map(sample)
+            .sum();
+        mono.push(sum / channels as f32);
+    }
+    Ok(mono)
+}
+
 pub struct WavEncoder;
 
 impl AudioEncoder for WavEncoder {
@@ -92,7 +252,7 @@ impl AudioEncoder for WavEncoder {
     }
 
     fn decode(&self, data: &[u8], _sample_rate: u32) -> Result<Vec<f32>> {
-        todo!()
+        decode_wav(data)
     }
 
     fn mime_type(&self) -> &'static str {
@@ -108,46 +268,62 @@ pub struct FlacEncoder;
 
 impl AudioEncoder for FlacEncoder {
     fn encode(&self, data: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
-        // Create a temporary WAV file
-        let temp_wav = NamedTempFile::new().map_err(|e| CodecError::Encoding(e.to_string()));
-        let wav_encoder = WavEncoder;
-        let wav_data = wav_encoder.encode(data, sample_rate)?;
-        temp_wav
-            .as_file()
-            .write_all(&wav_data)
-            .map_err(|e| CodecError::Encoding(e.to_string()));
-
-        // Use external FLAC encoder
-        let output = Command::new("flac")
-            .arg("--silent")
-            .arg("--force")
-            .arg("--stdout")
-            .arg(temp_wav.path())
-            .output()
-            .map_err(|e| {
-                error!("Failed to execute FLAC encoder: {}", e);
-                CodecError::Encoding(format!("Failed to execute FLAC encoder: {}", e))
-            });
+        // Encode directly from the in-memory samples with a pure-Rust FLAC
+        // encoder; no temp WAV and no `flac` subprocess.
+        const BITS_PER_SAMPLE: usize = 16;
 
-        if !output.status.success() {
-            let error_message = String::from_utf8_lossy(&output.stderr);
-            error!("FLAC encoding failed: {}", error_message);
-            return Err(CodecError::Encoding(format!(
-                "FLAC encoding failed: {}",
-                error_message
-            )));
-        }
+        let pcm: Vec<i32> = data
+            .iter()
+            .map(|&s| (s.clamp(-1.0, 1.0) * 32767.0) as i32)
+            .collect();
+
+        let config = flacenc::config::Encoder::default()
+            .into_verified()
+            .map_err(|e| CodecError::Encoding(format!("Invalid FLAC config: {:?}", e)))?;
+        let source =
+            flacenc::source::MemSource::from_samples(&pcm, 1, BITS_PER_SAMPLE, sample_rate as usize);
+        let stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+            .map_err(|e| CodecError::Encoding(format!("FLAC encoding failed: {:?}", e)))?;
+
+        let mut sink = flacenc::bitsink::ByteSink::new();
+        stream
+            .write(&mut sink)
+            .map_err(|e| CodecError::Encoding(format!("FLAC serialization failed: {:?}", e)))?;
+        let encoded = sink.into_inner();
 
         info!(
             "Encoded {} samples into {} bytes of FLAC data",
             data.len(),
-            output.stdout.len()
+            encoded.len()
         );
-        Ok(output.stdout)
+        Ok(encoded)
     }
 
     fn decode(&self, data: &[u8], _sample_rate: u32) -> Result<Vec<f32>> {
-        todo!()
+        // Decode in-process with a pure-Rust FLAC reader, downmixing to mono.
+        let mut reader = claxon::FlacReader::new(std::io::Cursor::new(data))
+            .map_err(|e| CodecError::Decoding(format!("Failed to read FLAC: {}", e)))?;
+
+        let channels = reader.streaminfo().channels.max(1) as usize;
+        let bits = reader.streaminfo().bits_per_sample;
+        let scale = (1i64 << (bits - 1)) as f32;
+
+        let mut mono = Vec::new();
+        let mut acc = 0i64;
+        let mut count = 0usize;
+        for sample in reader.samples() {
+            let sample = sample
+                .map_err(|e| CodecError::Decoding(format!("FLAC decode error: {}", e)))?;
+            acc += sample as i64;
+            count += 1;
+            if count == channels {
+                mono.push(acc as f32 / (channels as f32 * scale));
+                acc = 0;
+                count = 0;
+            }
+        }
+
+        Ok(mono)
     }
 
     fn mime_type(&self) -> &'static str {
@@ -171,52 +347,113 @@ impl OpusEncoder {
 
 impl AudioEncoder for OpusEncoder {
     fn encode(&self, data: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
-        // Create a temporary WAV file
-        let mut temp_wav: NamedTempFile = NamedTempFile::new()
-            .map_err(|e| CodecError::Encoding(format!("Failed to create temp WAV file: {}", e)))
-            .into()?;
-
-        // Write WAV data to the temporary file
-        let wav_encoder = WavEncoder;
-        let wav_data = wav_encoder.encode(data, sample_rate)?;
-        temp_wav
-            .write_all(&wav_data)
-            .map_err(|e| CodecError::Encoding(format!("Failed to write WAV data: {}", e)))?;
-
-        // Use FFmpeg to convert WAV to Opus
-        let output = Command::new("ffmpeg")
-            .arg("-i")
-            .arg(temp_wav.path())
-            .arg("-c:a")
-            .arg("libopus")
-            .arg("-b:a")
-            .arg(format!("{}k", self.bitrate))
-            .arg("-f")
-            .arg("opus")
-            .arg("-")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .map_err(|e| AudioError::Encoding(format!("Failed to execute FFmpeg: {}", e)))?;
-
-        if !output.status.success() {
-            let error_message = String::from_utf8_lossy(&output.stderr);
-            return Err(
-                AudioError::Encoding(format!("FFmpeg encoding failed: {}", error_message)).into(),
-            );
+        // Encode frame-by-frame with libopus and wrap the packets in an Ogg
+        // container in-process; no temp WAV and no FFmpeg.
+        const OPUS_RATE: u32 = 48000;
+        if sample_rate != OPUS_RATE {
+            return Err(CodecError::Encoding(format!(
+                "Opus requires {}Hz input, got {}Hz",
+                OPUS_RATE, sample_rate
+            ))
+            .into());
+        }
+
+        // 20ms frames at 48kHz.
+        const FRAME: usize = (OPUS_RATE as usize / 1000) * 20;
+
+        let mut encoder = opus::Encoder::new(OPUS_RATE, opus::Channels::Mono, opus::Application::Audio)
+            .map_err(|e| CodecError::Encoding(format!("Failed to create Opus encoder: {}", e)))?;
+        encoder
+            .set_bitrate(opus::Bitrate::Bits((self.bitrate * 1000) as i32))
+            .map_err(|e| CodecError::Encoding(format!("Failed to set Opus bitrate: {}", e)))?;
+
+        let mut writer = ogg::PacketWriter::new(Vec::new());
+
+        // OpusHead identification header (RFC 7845).
+        let mut head = Vec::with_capacity(19);
+        head.extend_from_slice(b"OpusHead");
+        head.push(1); // version
+        head.push(1); // channel count
+        head.extend_from_slice(&0u16.to_le_bytes()); // pre-skip
+        head.extend_from_slice(&OPUS_RATE.to_le_bytes()); // input sample rate
+        head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+        head.push(0); // channel mapping family
+        writer
+            .write_packet(head, OGG_SERIAL, ogg::PacketWriteEndInfo::EndPage, 0)
+            .map_err(|e| CodecError::Encoding(format!("Ogg write failed: {}", e)))?;
+
+        // OpusTags comment header.
+        let mut tags = Vec::new();
+        tags.extend_from_slice(b"OpusTags");
+        let vendor = b"life-logging";
+        tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+        tags.extend_from_slice(vendor);
+        tags.extend_from_slice(&0u32.to_le_bytes()); // user comment count
+        writer
+            .write_packet(tags, OGG_SERIAL, ogg::PacketWriteEndInfo::EndPage, 0)
+            .map_err(|e| CodecError::Encoding(format!("Ogg write failed: {}", e)))?;
+
+        let total_frames = data.len().div_ceil(FRAME);
+        let mut granule: u64 = 0;
+        for (i, chunk) in data.chunks(FRAME).enumerate() {
+            // Pad the final partial frame with silence.
+            let mut frame = [0f32; FRAME];
+            frame[..chunk.len()].copy_from_slice(chunk);
+
+            let packet = encoder
+                .encode_vec_float(&frame, FRAME)
+                .map_err(|e| CodecError::Encoding(format!("Opus encoding failed: {}", e)))?;
+
+            granule += FRAME as u64;
+            let end = if i + 1 == total_frames {
+                ogg::PacketWriteEndInfo::EndStream
+            } else {
+                ogg::PacketWriteEndInfo::NormalPacket
+            };
+            writer
+                .write_packet(packet, OGG_SERIAL, end, granule)
+                .map_err(|e| CodecError::Encoding(format!("Ogg write failed: {}", e)))?;
         }
 
+        let encoded = writer.into_inner();
         info!(
             "Encoded {} samples into {} bytes of Opus data at {}kbps",
             data.len(),
-            output.stdout.len(),
+            encoded.len(),
             self.bitrate
         );
-        Ok(output.stdout)
+        Ok(encoded)
     }
 
     fn decode(&self, data: &[u8], _sample_rate: u32) -> Result<Vec<f32>> {
-        todo!()
+        // Demux the Ogg container and decode each Opus packet in-process,
+        // downmixing stereo output to mono.
+        const OPUS_RATE: u32 = 48000;
+        let mut packet_reader = ogg::PacketReader::new(std::io::Cursor::new(data));
+        let mut decoder = opus::Decoder::new(OPUS_RATE, opus::Channels::Mono)
+            .map_err(|e| CodecError::Decoding(format!("Failed to create Opus decoder: {}", e)))?;
+
+        let mut samples = Vec::new();
+        let mut frame = vec![0f32; OPUS_RATE as usize]; // 1s scratch buffer
+        while let Some(packet) = packet_reader
+            .read_packet()
+            .map_err(|e| CodecError::Decoding(format!("Ogg demux error: {}", e)))?
+        {
+            // Skip the OpusHead / OpusTags identification packets.
+            if packet.data.starts_with(b"OpusHead") || packet.data.starts_with(b"OpusTags") {
+                continue;
+            }
+            let decoded = decoder
+                .decode_float(&packet.data, &mut frame, false)
+                .map_err(|e| CodecError::Decoding(format!("Opus decode error: {}", e)))?;
+            samples.extend_from_slice(&frame[..decoded]);
+        }
+
+        Ok(samples)
+    }
+
+    fn required_sample_rate(&self) -> Option<u32> {
+        Some(48000)
     }
 
     fn mime_type(&self) -> &'static str {
@@ -228,6 +465,111 @@ impl AudioEncoder for OpusEncoder {
     }
 }
 
+pub struct Mp3Encoder {
+    bitrate: u32,
+}
+
+impl Mp3Encoder {
+    pub fn new(bitrate: u32) -> Self {
+        Mp3Encoder { bitrate }
+    }
+
+    fn brate(&self) -> mp3lame_encoder::Bitrate {
+        use mp3lame_encoder::Bitrate;
+        match self.bitrate {
+            0..=40 => Bitrate::Kbps32,
+            41..=56 => Bitrate::Kbps48,
+            57..=72 => Bitrate::Kbps64,
+            73..=104 => Bitrate::Kbps96,
+            105..=144 => Bitrate::Kbps128,
+            145..=208 => Bitrate::Kbps192,
+            209..=288 => Bitrate::Kbps256,
+            _ => Bitrate::Kbps320,
+        }
+    }
+}
+
+impl AudioEncoder for Mp3Encoder {
+    fn encode(&self, data: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
+        use mp3lame_encoder::{Builder, FlushNoGap, MonoPcm};
+
+        let mut builder = Builder::new()
+            .ok_or_else(|| CodecError::Encoding("Failed to create LAME builder".into()))?;
+        builder
+            .set_num_channels(1)
+            .map_err(|e| CodecError::Encoding(format!("Failed to set channels: {}", e)))?;
+        builder
+            .set_sample_rate(sample_rate)
+            .map_err(|e| CodecError::Encoding(format!("Failed to set sample rate: {}", e)))?;
+        builder
+            .set_brate(self.brate())
+            .map_err(|e| CodecError::Encoding(format!("Failed to set bitrate: {}", e)))?;
+        builder
+            .set_quality(mp3lame_encoder::Quality::Best)
+            .map_err(|e| CodecError::Encoding(format!("Failed to set quality: {}", e)))?;
+
+        let mut encoder = builder
+            .build()
+            .map_err(|e| CodecError::Encoding(format!("Failed to build LAME encoder: {}", e)))?;
+
+        let pcm: Vec<i16> = data
+            .iter()
+            .map(|&s| (s.clamp(-1.0, 1.0) * 32767.0) as i16)
+            .collect();
+
+        let mut buffer = Vec::with_capacity(mp3lame_encoder::max_required_buffer_size(pcm.len()));
+        let written = encoder
+            .encode(MonoPcm(&pcm), buffer.spare_capacity_mut())
+            .map_err(|e| CodecError::Encoding(format!("MP3 encoding failed: {}", e)))?;
+        unsafe { buffer.set_len(buffer.len() + written) };
+
+        let written = encoder
+            .flush::<FlushNoGap>(buffer.spare_capacity_mut())
+            .map_err(|e| CodecError::Encoding(format!("MP3 flush failed: {}", e)))?;
+        unsafe { buffer.set_len(buffer.len() + written) };
+
+        info!(
+            "Encoded {} samples into {} bytes of MP3 data at {}kbps",
+            data.len(),
+            buffer.len(),
+            self.bitrate
+        );
+        Ok(buffer)
+    }
+
+    fn decode(&self, data: &[u8], _sample_rate: u32) -> Result<Vec<f32>> {
+        let mut decoder = minimp3::Decoder::new(data);
+        let mut samples = Vec::new();
+        loop {
+            match decoder.next_frame() {
+                Ok(minimp3::Frame { data, channels, .. }) => {
+                    if channels <= 1 {
+                        samples.extend(data.iter().map(|&s| s as f32 / 32768.0));
+                    } else {
+                        for frame in data.chunks_exact(channels) {
+                            let sum: i32 = frame.iter().map(|&s| s as i32).sum();
+                            samples.push(sum as f32 / (channels as f32 * 32768.0));
+                        }
+                    }
+                }
+                Err(minimp3::Error::Eof) => break,
+                Err(e) => {
+                    return Err(CodecError::Decoding(format!("MP3 decoding failed: {}", e)).into())
+                }
+            }
+        }
+        Ok(samples)
+    }
+
+    fn mime_type(&self) -> &'static str {
+        "audio/mpeg"
+    }
+
+    fn content_disposition(&self) -> &'static str {
+        "attachment; filename=\"audio.mp3\""
+    }
+}
+
 pub struct MoshiEncoder {
     model: Arc<Mutex<Encodec>>,
     device: Device,
@@ -312,6 +654,10 @@ impl AudioEncoder for MoshiEncoder {
         Ok(decoded_samples)
     }
 
+    fn required_sample_rate(&self) -> Option<u32> {
+        Some(24000)
+    }
+
     fn mime_type(&self) -> &'static str {
         "application/x-moshi"
     }
@@ -358,6 +704,18 @@ impl EncoderFactory {
             "opus64".to_string(),
             Box::new(OpusEncoder::new(64)) as Box<dyn AudioEncoder>,
         );
+        encoders.insert(
+            "mp3".to_string(), // Default to 128kbps
+            Box::new(Mp3Encoder::new(128)) as Box<dyn AudioEncoder>,
+        );
+        encoders.insert(
+            "mp3_128".to_string(),
+            Box::new(Mp3Encoder::new(128)) as Box<dyn AudioEncoder>,
+        );
+        encoders.insert(
+            "mp3_320".to_string(),
+            Box::new(Mp3Encoder::new(320)) as Box<dyn AudioEncoder>,
+        );
         encoders.insert(
             "moshi".to_string(),
             Box::new(MoshiEncoder::new().expect("Failed to initialize MoshiEncoder"))
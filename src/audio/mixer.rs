@@ -0,0 +1,118 @@
+//! Multi-source audio mixer: independent input sources (e.g. microphone +
+//! system loopback) each accumulate into their own small ring, and
+//! [`AudioMixer::tick`] sums one frame from each into the shared
+//! [`AudioBuffer`] on a fixed cadence, resampling and gain-scaling per
+//! source along the way.
+
+use crate::audio::buffer::{AudioBuffer, CircularBuffer};
+use crate::audio::resample::{resample, InterpolationMode};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::RwLock;
+
+/// One input source's accumulation ring plus the gain applied to it when
+/// the mixer sums frames together.
+struct AudioSource {
+    ring: CircularBuffer<f32>,
+    sample_rate: u32,
+    gain: f32,
+    /// Count of `fill_with` calls, so a source that's gone quiet can be
+    /// spotted without inspecting sample values.
+    sequence: AtomicU64,
+}
+
+/// Handle returned by [`AudioMixer::add_source`]; the only way callers push
+/// samples into (or adjust the gain of) a registered source.
+#[derive(Clone)]
+pub struct SourceHandle {
+    source: Arc<Mutex<AudioSource>>,
+}
+
+impl SourceHandle {
+    /// Enqueue newly-captured samples from this source.
+    pub fn fill_with(&self, samples: &[f32]) {
+        let mut source = self.source.lock().unwrap();
+        source.ring.write(samples);
+        source.sequence.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Linear gain applied to this source's contribution to the mix.
+    pub fn set_gain(&self, gain: f32) {
+        self.source.lock().unwrap().gain = gain;
+    }
+
+    pub fn gain(&self) -> f32 {
+        self.source.lock().unwrap().gain
+    }
+
+    /// Number of times this source has been filled, for diagnosing a source
+    /// that isn't producing audio.
+    pub fn sequence(&self) -> u64 {
+        self.source.lock().unwrap().sequence.load(Ordering::Relaxed)
+    }
+}
+
+/// Sums frames from every registered source into a shared [`AudioBuffer`],
+/// resampling each source to the target's sample rate and soft-clipping the
+/// sum so several loud sources together can't wrap around.
+pub struct AudioMixer {
+    target: Arc<RwLock<AudioBuffer>>,
+    frame_size: usize,
+    sources: Mutex<Vec<Arc<Mutex<AudioSource>>>>,
+}
+
+impl AudioMixer {
+    pub fn new(target: Arc<RwLock<AudioBuffer>>, frame_size: usize) -> Self {
+        Self {
+            target,
+            frame_size,
+            sources: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Register a new input source at `sample_rate` with unity gain,
+    /// returning a handle the caller uses to feed it captured samples.
+    pub fn add_source(&self, sample_rate: u32) -> SourceHandle {
+        let source = Arc::new(Mutex::new(AudioSource {
+            ring: CircularBuffer::new(self.frame_size * 4),
+            sample_rate,
+            gain: 1.0,
+            sequence: AtomicU64::new(0),
+        }));
+        self.sources.lock().unwrap().push(source.clone());
+        SourceHandle { source }
+    }
+
+    /// Pull one frame from every source, resample/gain/sum them, soft-clip
+    /// the result, and write it into the target buffer. Call this on a
+    /// fixed cadence (e.g. every `frame_size / target_sample_rate` seconds).
+    pub async fn tick(&self) {
+        let target_rate = self.target.read().await.get_sample_rate();
+        let mut mixed = vec![0.0f32; self.frame_size];
+
+        for source in self.sources.lock().unwrap().iter() {
+            let source = source.lock().unwrap();
+            let frame = source.ring.read(self.frame_size);
+            let frame = if source.sample_rate == target_rate {
+                frame
+            } else {
+                resample(
+                    &frame,
+                    source.sample_rate,
+                    target_rate,
+                    InterpolationMode::Linear,
+                )
+            };
+
+            for (m, &s) in mixed.iter_mut().zip(frame.iter()) {
+                *m += s * source.gain;
+            }
+        }
+
+        for sample in mixed.iter_mut() {
+            *sample = sample.tanh();
+        }
+
+        self.target.write().await.write(&mixed);
+    }
+}
@@ -0,0 +1,59 @@
+//! Output/playback path, mirroring `processor::start_audio_stream`'s input
+//! setup but in the other direction: stage PCM in a ring buffer and let a
+//! `build_output_stream` callback drain it, so auditioning captured audio
+//! doesn't require leaving the process.
+
+use crate::error::AudioError;
+use crate::prelude::*;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{error, info};
+
+/// Play `samples` (mono, at `sample_rate`) through the host's default output
+/// device, blocking until the buffer has fully drained.
+pub fn play_samples(samples: Vec<f32>, sample_rate: u32) -> Result<()> {
+    let host = cpal::default_host();
+    let device = host
+        .default_output_device()
+        .ok_or_else(|| AudioError::Device("No default output device".into()))?;
+
+    let mut config = device.default_output_config()?.config();
+    config.channels = 1;
+    config.sample_rate = cpal::SampleRate(sample_rate);
+
+    let total_samples = samples.len();
+    let ring = Arc::new(Mutex::new(VecDeque::from(samples)));
+    let ring_cb = ring.clone();
+    let drained = Arc::new(AtomicBool::new(false));
+    let drained_cb = drained.clone();
+
+    let stream = device.build_output_stream(
+        &config,
+        move |output: &mut [f32], _: &cpal::OutputCallbackInfo| {
+            let mut ring = ring_cb.lock().unwrap();
+            for sample in output.iter_mut() {
+                *sample = ring.pop_front().unwrap_or(0.0);
+            }
+            if ring.is_empty() {
+                drained_cb.store(true, Ordering::Relaxed);
+            }
+        },
+        move |err| error!("Playback stream error: {}", err),
+        Some(Duration::from_secs(2)),
+    )?;
+
+    info!("Playing {} samples at {}Hz", total_samples, sample_rate);
+    stream.play()?;
+
+    while !drained.load(Ordering::Relaxed) {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    // Give cpal's own internal buffering time to flush before the stream
+    // (and the device) is torn down.
+    std::thread::sleep(Duration::from_millis(200));
+
+    Ok(())
+}
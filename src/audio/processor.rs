@@ -1,24 +1,115 @@
 use crate::app_state::AppState;
 use crate::audio::buffer::AudioBuffer;
+use crate::audio::mixer::{AudioMixer, SourceHandle};
 use crate::config::CONFIG_MANAGER;
 use crate::prelude::*;
-use cpal::traits::{DeviceTrait, StreamTrait};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::Stream;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{broadcast, mpsc, RwLock};
 use tracing::{error, info, instrument, warn};
 
+/// A control message to the audio stream management task.
+#[derive(Debug, Clone)]
+pub enum DeviceCommand {
+    /// Tear down the running stream and rebuild it on the named input device.
+    Switch(String),
+}
+
+/// What knocked the running stream over, sent on the per-attempt `tx` channel
+/// so the management loop can tell a recoverable device move from a hard
+/// callback error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StreamEvent {
+    /// The input callback reported an error (device unplugged, format
+    /// rejected mid-stream, etc.) — back off before retrying.
+    CallbackError,
+    /// `DeviceWatcher` observed the OS default input device or its nominal
+    /// sample rate change out from under us — rebuild immediately.
+    DeviceChanged,
+}
+
+/// Polls the OS default input device's name and nominal sample rate for
+/// changes relative to the stream that's currently running, and pushes a
+/// `StreamEvent::DeviceChanged` when either one moves.
+///
+/// cpal doesn't expose the native device-change notifications each platform
+/// offers (CoreAudio's `AudioObjectAddPropertyListener` on
+/// `kAudioDevicePropertyNominalSampleRate` and the default-device property,
+/// WASAPI's `IMMNotificationClient`, ALSA's hotplug events), so this is a
+/// cheap periodic poll standing in for them; swap in the native listener
+/// per-platform if cpal grows one.
+struct DeviceWatcher {
+    stop: Arc<AtomicBool>,
+}
+
+impl DeviceWatcher {
+    /// Start watching in the background. `device_name` is the device the
+    /// running stream was explicitly pinned to, if any — an explicit pin
+    /// doesn't move when the OS default changes, so there's nothing to watch.
+    fn spawn(device_name: Option<String>, running_sample_rate: u32, tx: mpsc::Sender<StreamEvent>) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_clone = stop.clone();
+
+        std::thread::spawn(move || {
+            if device_name.is_some() {
+                return;
+            }
+
+            let host = cpal::default_host();
+            let baseline_name = host.default_input_device().and_then(|d| d.name().ok());
+
+            while !stop_clone.load(Ordering::Relaxed) {
+                std::thread::sleep(Duration::from_secs(1));
+                if stop_clone.load(Ordering::Relaxed) {
+                    return;
+                }
+
+                let Some(device) = host.default_input_device() else {
+                    continue;
+                };
+                let name = device.name().ok();
+                let rate = device.default_input_config().ok().map(|c| c.sample_rate().0);
+
+                let device_changed = name != baseline_name;
+                let rate_changed = rate.is_some_and(|r| r != running_sample_rate);
+
+                if device_changed || rate_changed {
+                    let _ = tx.blocking_send(StreamEvent::DeviceChanged);
+                    return;
+                }
+            }
+        });
+
+        Self { stop }
+    }
+}
+
+impl Drop for DeviceWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
 #[instrument(skip(app_state))]
 pub async fn setup_audio_processing(app_state: Arc<AppState>) -> Result<()> {
     info!("Setting up audio processing");
 
     let audio_sender = app_state.audio_sender.clone();
-    let audio_buffer = app_state.audio_buffer.clone();
+    let mic_source = app_state.mic_source.clone();
+    let recording = app_state.recording.clone();
 
     tokio::spawn(async move {
         let mut audio_receiver = audio_sender.subscribe();
-        audio_processing_task(audio_buffer, &mut audio_receiver).await;
+        audio_processing_task(mic_source, recording, &mut audio_receiver).await;
+    });
+
+    let mixer = app_state.mixer.clone();
+    let recording = app_state.recording.clone();
+    tokio::spawn(async move {
+        mixer_tick_task(mixer, recording).await;
     });
 
     let app_state_clone = app_state.clone();
@@ -29,16 +120,78 @@ pub async fn setup_audio_processing(app_state: Arc<AppState>) -> Result<()> {
     Ok(())
 }
 
-#[instrument(skip(audio_buffer, audio_receiver))]
+/// Sum every registered [`AudioMixer`] source into the shared buffer on a
+/// fixed 20ms cadence, matching the frame size sources are registered with
+/// in `AppState::new`.
+#[instrument(skip(mixer, recording))]
+async fn mixer_tick_task(mixer: Arc<AudioMixer>, recording: Arc<crate::app_state::RecordingControl>) {
+    use std::sync::atomic::Ordering;
+
+    info!("Starting audio mixer tick task");
+    let mut interval = tokio::time::interval(Duration::from_millis(20));
+    loop {
+        interval.tick().await;
+        // While injection is active, `inject_staged_audio` drives the
+        // buffer directly; ticking the mixer here too would interleave
+        // silence from the (unfed) mic source into the injected signal.
+        if recording.injecting.load(Ordering::Relaxed) {
+            continue;
+        }
+        mixer.tick().await;
+    }
+}
+
+#[instrument(skip(mic_source, recording, audio_receiver))]
 async fn audio_processing_task(
-    audio_buffer: Arc<RwLock<AudioBuffer>>,
+    mic_source: SourceHandle,
+    recording: Arc<crate::app_state::RecordingControl>,
     audio_receiver: &mut broadcast::Receiver<Vec<f32>>,
 ) {
+    use std::sync::atomic::Ordering;
+
     info!("Starting audio processing task");
 
     while let Ok(data) = audio_receiver.recv().await {
-        let mut buffer = audio_buffer.write().await;
-        buffer.write(&data);
+        // While injection is active, live capture is suppressed; the injection
+        // task drives the buffer instead.
+        if recording.injecting.load(Ordering::Relaxed) {
+            continue;
+        }
+
+        mic_source.fill_with(&data);
+
+        // Tee the samples into the side recording when output capture is on.
+        if recording.output_saving.load(Ordering::Relaxed) {
+            recording.output.lock().unwrap().extend_from_slice(&data);
+        }
+    }
+}
+
+/// Stream the staged input buffer into `audio_buffer` in real-time-ish chunks
+/// while injection is active, tee-ing into the output recording as the live
+/// path would. Returns once the staged buffer is exhausted or injection stops.
+pub async fn inject_staged_audio(
+    audio_buffer: Arc<RwLock<AudioBuffer>>,
+    recording: Arc<crate::app_state::RecordingControl>,
+) {
+    use std::sync::atomic::Ordering;
+
+    let staged = recording.staged_input.lock().unwrap().clone();
+    let sample_rate = audio_buffer.read().await.get_sample_rate();
+    let chunk = (sample_rate as usize / 50).max(1); // ~20ms frames
+
+    for frame in staged.chunks(chunk) {
+        if !recording.injecting.load(Ordering::Relaxed) {
+            break;
+        }
+        {
+            let mut buffer = audio_buffer.write().await;
+            buffer.write(frame);
+        }
+        if recording.output_saving.load(Ordering::Relaxed) {
+            recording.output.lock().unwrap().extend_from_slice(frame);
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
     }
 }
 
@@ -48,9 +201,23 @@ fn audio_stream_management_task(app_state: Arc<AppState>) {
 
     let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
 
+    // The device-control receiver is handed over from AppState exactly once.
+    let mut device_rx = app_state
+        .device_rx
+        .lock()
+        .expect("device control receiver poisoned")
+        .take()
+        .expect("device control receiver already taken");
+
     loop {
-        let (tx, mut rx) = mpsc::channel::<()>(1);
-        match rt.block_on(start_audio_stream(&app_state, tx)) {
+        let (tx, mut rx) = mpsc::channel::<StreamEvent>(4);
+        let requested_device = app_state
+            .active_device
+            .read()
+            .expect("active device poisoned")
+            .clone();
+
+        match rt.block_on(start_audio_stream(&app_state, tx.clone(), requested_device.clone())) {
             Ok((stream, new_sample_rate)) => {
                 rt.block_on(async {
                     let mut audio_buffer = app_state.audio_buffer.write().await;
@@ -67,8 +234,38 @@ fn audio_stream_management_task(app_state: Arc<AppState>) {
                     continue;
                 }
 
-                // Wait for the stream to end or for an error
-                rt.block_on(async { rx.recv().await });
+                let watcher = DeviceWatcher::spawn(requested_device, new_sample_rate, tx);
+
+                // Rebuild immediately on an explicit device switch or a
+                // detected device/format change; back off on a stream error.
+                let rebuild_now = rt.block_on(async {
+                    tokio::select! {
+                        event = rx.recv() => match event {
+                            Some(StreamEvent::DeviceChanged) => {
+                                info!("Default input device or sample rate changed");
+                                true
+                            }
+                            Some(StreamEvent::CallbackError) | None => false,
+                        },
+                        cmd = device_rx.recv() => match cmd {
+                            Some(DeviceCommand::Switch(name)) => {
+                                *app_state.active_device.write().expect("active device poisoned") =
+                                    Some(name);
+                                true
+                            }
+                            None => false,
+                        },
+                    }
+                });
+
+                // Dropping `stream` tears down the old device before we rebuild;
+                // dropping the watcher stops its polling thread.
+                drop(stream);
+                drop(watcher);
+                if rebuild_now {
+                    info!("Rebuilding audio stream for device switch");
+                    continue;
+                }
             }
             Err(e) => {
                 error!("Failed to start audio stream: {}", e);
@@ -85,11 +282,25 @@ fn audio_stream_management_task(app_state: Arc<AppState>) {
 #[instrument(skip(app_state, tx))]
 async fn start_audio_stream(
     app_state: &Arc<AppState>,
-    tx: mpsc::Sender<()>,
+    tx: mpsc::Sender<StreamEvent>,
+    device_name: Option<String>,
 ) -> Result<(Stream, u32)> {
     info!("Starting audio stream");
 
-    let (device, config) = CONFIG_MANAGER.get_audio_config().await?;
+    // Resolve the requested device by name among the host's input devices,
+    // falling back to the configured default when none is requested or matched.
+    let (device, config) = match device_name {
+        Some(name) => {
+            let host = cpal::default_host();
+            let device = host
+                .input_devices()?
+                .find(|d| d.name().map(|n| n == name).unwrap_or(false))
+                .ok_or_else(|| AudioError::Device(format!("Input device not found: {}", name)))?;
+            let config = device.default_input_config()?.config();
+            (device, config)
+        }
+        None => CONFIG_MANAGER.get_audio_config().await?,
+    };
     let audio_sender = app_state.audio_sender.clone();
 
     let tx1 = tx.clone();
@@ -100,12 +311,12 @@ async fn start_audio_stream(
         move |data: &[f32], _: &cpal::InputCallbackInfo| {
             if let Err(e) = audio_sender.send(data.to_vec()) {
                 warn!("Failed to send audio data: {}", e);
-                let _ = tx1.try_send(());
+                let _ = tx1.try_send(StreamEvent::CallbackError);
             }
         },
         move |err| {
             error!("An error occurred on stream: {}", err);
-            let _ = tx2.try_send(());
+            let _ = tx2.try_send(StreamEvent::CallbackError);
         },
         Some(Duration::from_secs(2)),
     )?;
@@ -0,0 +1,167 @@
+//! Band-limited (windowed-sinc) resampling shared by the in-process codecs,
+//! plus the cheaper interpolation modes `AudioBuffer::update_sample_rate`
+//! and friends can fall back to when the full sinc kernel isn't worth the
+//! cost.
+//!
+//! A plain linear interpolator aliases badly and colors the audio whenever
+//! the capture rate (e.g. 44.1kHz) differs from a codec's native rate (e.g.
+//! Opus's 48kHz). `Resampler` instead precomputes nothing up front and
+//! evaluates a windowed-sinc kernel per output sample: for each output
+//! position `p = i / ratio`, it sums `input[floor(p) + k] * h(frac(p), k)`
+//! over `k` in `[-taps, taps]`, with zero-padding at the buffer edges and a
+//! weight-sum normalization so DC is preserved.
+
+/// A windowed-sinc resampler between a fixed input and output rate.
+#[derive(Debug, Clone, Copy)]
+pub struct Resampler {
+    input_rate: u32,
+    output_rate: u32,
+    /// Half-width of the sinc kernel, in input taps either side of the
+    /// sample position. Larger values sharpen the transition band at extra
+    /// CPU cost; 16-32 is a reasonable range.
+    taps: isize,
+}
+
+impl Resampler {
+    pub fn new(input_rate: u32, output_rate: u32, taps: isize) -> Self {
+        Self {
+            input_rate,
+            output_rate,
+            taps,
+        }
+    }
+
+    /// Resample `data` from `input_rate` to `output_rate`.
+    pub fn process(&self, data: &[f32]) -> Vec<f32> {
+        if self.input_rate == self.output_rate {
+            return data.to_vec();
+        }
+        if data.is_empty() {
+            return Vec::new();
+        }
+
+        let new_len =
+            (data.len() as f64 * self.output_rate as f64 / self.input_rate as f64).round() as usize;
+        // Anti-alias cutoff: only tighten the passband when downsampling.
+        let fc = (self.output_rate as f64 / self.input_rate as f64).min(1.0);
+        let step = self.input_rate as f64 / self.output_rate as f64;
+
+        let mut resampled = Vec::with_capacity(new_len);
+        for n in 0..new_len {
+            let p = n as f64 * step;
+            let base = p.floor() as isize;
+
+            let mut acc = 0.0f64;
+            let mut weight_sum = 0.0f64;
+            for k in (base - self.taps)..=(base + self.taps) {
+                let t = k as f64 - p;
+                let w = blackman(t / self.taps as f64);
+                if w == 0.0 {
+                    continue;
+                }
+                let weight = fc * sinc(fc * t) * w;
+                // Samples outside the buffer contribute zero.
+                if k >= 0 && (k as usize) < data.len() {
+                    acc += data[k as usize] as f64 * weight;
+                    weight_sum += weight;
+                }
+            }
+
+            // Normalize by applied weight to preserve amplitude at the edges,
+            // where the kernel is clipped by the buffer boundary.
+            let sample = if weight_sum.abs() > f64::EPSILON {
+                acc / weight_sum
+            } else {
+                0.0
+            };
+            resampled.push(sample as f32);
+        }
+
+        resampled
+    }
+}
+
+/// Interpolation strategy for a one-off sample-rate conversion. `Nearest`
+/// and `Linear` are cheap fallbacks; `Cubic` (Catmull-Rom) is a reasonable
+/// middle ground; `PolyphaseSinc` is `Resampler`'s band-limited windowed-sinc
+/// kernel, the highest quality but the most CPU per sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterpolationMode {
+    Nearest,
+    Linear,
+    Cubic,
+    PolyphaseSinc,
+}
+
+/// Resample `data` from `in_rate` to `out_rate` using `mode`.
+pub fn resample(data: &[f32], in_rate: u32, out_rate: u32, mode: InterpolationMode) -> Vec<f32> {
+    if in_rate == out_rate {
+        return data.to_vec();
+    }
+    if data.is_empty() {
+        return Vec::new();
+    }
+
+    if mode == InterpolationMode::PolyphaseSinc {
+        return Resampler::new(in_rate, out_rate, 16).process(data);
+    }
+
+    let new_len = (data.len() as f64 * out_rate as f64 / in_rate as f64).ceil() as usize;
+    let step = in_rate as f64 / out_rate as f64;
+    let last = data.len() - 1;
+
+    (0..new_len)
+        .map(|n| {
+            let pos = n as f64 * step;
+            match mode {
+                InterpolationMode::Nearest => data[(pos.round() as usize).min(last)],
+                InterpolationMode::Linear => {
+                    let i = pos.floor() as usize;
+                    let frac = (pos - pos.floor()) as f32;
+                    data[i.min(last)] * (1.0 - frac) + data[(i + 1).min(last)] * frac
+                }
+                InterpolationMode::Cubic => {
+                    let i = pos.floor() as isize;
+                    let frac = (pos - pos.floor()) as f32;
+                    let at = |k: isize| -> f32 { data[k.clamp(0, last as isize) as usize] };
+                    catmull_rom(at(i - 1), at(i), at(i + 1), at(i + 2), frac)
+                }
+                InterpolationMode::PolyphaseSinc => unreachable!("handled above"),
+            }
+        })
+        .collect()
+}
+
+/// 4-tap Catmull-Rom cubic interpolation between `p1` and `p2` at `t` in
+/// `[0, 1)`, using `p0`/`p3` as the neighboring control points.
+fn catmull_rom(p0: f32, p1: f32, p2: f32, p3: f32, t: f32) -> f32 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * ((2.0 * p1)
+        + (p2 - p0) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
+
+/// Normalized sinc, `sin(pi x) / (pi x)`, with `sinc(0) = 1`.
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        1.0
+    } else {
+        let pix = std::f64::consts::PI * x;
+        pix.sin() / pix
+    }
+}
+
+/// Blackman window, non-zero only on `[-1, 1]`.
+fn blackman(t: f64) -> f64 {
+    if t.abs() >= 1.0 {
+        0.0
+    } else {
+        const A0: f64 = 0.42;
+        const A1: f64 = 0.5;
+        const A2: f64 = 0.08;
+        let x = std::f64::consts::PI * t;
+        A0 + A1 * x.cos() + A2 * (2.0 * x).cos()
+    }
+}
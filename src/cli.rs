@@ -0,0 +1,95 @@
+//! Small offline CLI verbs for auditioning the logger without the HTTP API:
+//! `record <seconds> <file>` captures straight from the default input device
+//! and encodes with the codec implied by the file extension; `play <file>`
+//! decodes a file and plays it back through the default output device.
+
+use crate::audio::codec::CODEC_FACTORY;
+use crate::audio::playback;
+use crate::error::AudioError;
+use crate::prelude::*;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::info;
+
+fn codec_for_path(path: &str) -> Result<std::sync::Arc<dyn crate::audio::codec::Codec>> {
+    let extension = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("wav");
+
+    CODEC_FACTORY
+        .get(extension)
+        .ok_or_else(|| AudioError::UnsupportedFormat(extension.to_string()).into())
+}
+
+/// `record <seconds> <output file>`: capture from the default input device
+/// for `seconds` and write the encoded result to `output file`.
+pub async fn record(args: &[String]) -> Result<()> {
+    let seconds: f32 = args
+        .first()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| AudioError::Device("usage: record <seconds> <output file>".into()))?;
+    let path = args
+        .get(1)
+        .ok_or_else(|| AudioError::Device("usage: record <seconds> <output file>".into()))?;
+
+    let codec = codec_for_path(path)?;
+
+    let host = cpal::default_host();
+    let device = host
+        .default_input_device()
+        .ok_or_else(|| AudioError::Device("No default input device".into()))?;
+    let config = device.default_input_config()?.config();
+    let sample_rate = config.sample_rate.0;
+
+    let captured = Arc::new(Mutex::new(Vec::new()));
+    let captured_cb = captured.clone();
+    let stream = device.build_input_stream(
+        &config,
+        move |data: &[f32], _: &cpal::InputCallbackInfo| {
+            captured_cb.lock().unwrap().extend_from_slice(data);
+        },
+        move |err| tracing::error!("Recording stream error: {}", err),
+        Some(Duration::from_secs(2)),
+    )?;
+
+    stream.play()?;
+    tokio::time::sleep(Duration::from_secs_f32(seconds)).await;
+    drop(stream);
+
+    let samples = Arc::try_unwrap(captured)
+        .map_err(|_| AudioError::Device("recording stream still held a reference".into()))?
+        .into_inner()
+        .unwrap();
+
+    let encoded = codec.encode(&samples, sample_rate)?;
+    std::fs::write(path, encoded)?;
+
+    info!(
+        "Recorded {:.1}s ({} samples) to {}",
+        seconds,
+        samples.len(),
+        path
+    );
+    Ok(())
+}
+
+/// `play <input file>`: decode `input file` with the codec implied by its
+/// extension and play it through the default output device.
+pub async fn play(args: &[String]) -> Result<()> {
+    let path = args
+        .first()
+        .ok_or_else(|| AudioError::Device("usage: play <input file>".into()))?;
+
+    let codec = codec_for_path(path)?;
+    let data = std::fs::read(path)?;
+
+    // The codecs that need a fixed native rate (Opus) ignore this and decode
+    // at their own rate; PCM formats decode at whatever rate they encoded at.
+    let sample_rate = 48000;
+    let samples = codec.decode(&data, sample_rate)?;
+
+    info!("Playing {} ({} samples)", path, samples.len());
+    playback::play_samples(samples, sample_rate)
+}
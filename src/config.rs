@@ -12,6 +12,10 @@ use tracing::{info, warn};
 pub struct Config {
     pub buffer_duration: u64,
     pub server: ServerSettings,
+    #[serde(default)]
+    pub encryption: Option<EncryptionSettings>,
+    #[serde(default)]
+    pub audio: AudioSettings,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -20,6 +24,34 @@ pub struct ServerSettings {
     pub port: u16,
 }
 
+/// Input-device and capture-format preferences, all optional so an empty
+/// `[audio]` table (or none at all) falls back to "first working device at
+/// its default config", the prior behavior.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct AudioSettings {
+    /// Exact `cpal::Device::name()` to prefer. Enumeration still falls
+    /// through to the rest of the host's devices if this one is absent or
+    /// unsupported, so a restart recovers from an unplugged mic instead of
+    /// failing outright.
+    pub device_name: Option<String>,
+    /// Sample rate to request from the chosen device, used only when one of
+    /// its supported ranges contains it exactly; falls back to the device's
+    /// default/max-rate config if no range can satisfy it.
+    pub preferred_sample_rate: Option<u32>,
+    /// Sample format to request (`"f32"`, `"i16"`, or `"u16"`), falling
+    /// back the same way.
+    pub sample_format: Option<String>,
+}
+
+/// At-rest encryption for persisted audio segments, absent by default so
+/// existing deployments aren't surprised by unreadable archives. Settable
+/// via `config/*.toml`'s `[encryption]` table or `LIFELOGGING__ENCRYPTION__KEY`.
+#[derive(Debug, Deserialize, Clone)]
+pub struct EncryptionSettings {
+    /// Hex-encoded 32-byte ChaCha20-Poly1305 key.
+    pub key: String,
+}
+
 pub static CONFIG_MANAGER: Lazy<ConfigManager> =
     Lazy::new(|| ConfigManager::new().expect("Failed to initialize ConfigManager"));
 
@@ -53,20 +85,28 @@ impl ConfigManager {
 
     pub async fn get_audio_config(&self) -> Result<(cpal::Device, StreamConfig)> {
         let host = cpal::default_host();
-        self.find_working_device_and_config(&host).await
+        let settings = self.config.read().await.audio.clone();
+        self.find_working_device_and_config(&host, &settings).await
     }
 
     async fn find_working_device_and_config(
         &self,
         host: &cpal::Host,
+        settings: &AudioSettings,
     ) -> Result<(cpal::Device, StreamConfig)> {
-        let devices = host.input_devices()?;
+        let mut devices: Vec<cpal::Device> = host.input_devices()?.collect();
+
+        // Try the configured device first, then fall through the rest of
+        // the host's devices in their original enumeration order.
+        if let Some(wanted) = &settings.device_name {
+            devices.sort_by_key(|d| d.name().ok().as_deref() != Some(wanted.as_str()));
+        }
 
         for device in devices {
             let name = device.name()?;
             info!("Checking device: {}", name);
 
-            match self.find_supported_config(&device).await {
+            match self.find_supported_config(&device, settings).await {
                 Ok(stream_config) => {
                     info!(
                         "Found working config for device {}: {:?}",
@@ -87,11 +127,43 @@ impl ConfigManager {
         )
     }
 
-    async fn find_supported_config(&self, device: &cpal::Device) -> Result<StreamConfig> {
-        let supported_configs = device.supported_input_configs()?;
+    async fn find_supported_config(
+        &self,
+        device: &cpal::Device,
+        settings: &AudioSettings,
+    ) -> Result<StreamConfig> {
+        let wanted_format = settings.sample_format.as_deref().and_then(parse_sample_format);
+        let supported_configs: Vec<_> = device.supported_input_configs()?.collect();
+
+        // A range covering the requested rate, preferring one that also
+        // matches the requested format.
+        if let Some(rate) = settings.preferred_sample_rate {
+            if let Some(range) = supported_configs.iter().find(|range| {
+                wanted_format.map_or(true, |f| range.sample_format() == f)
+                    && range.min_sample_rate().0 <= rate
+                    && rate <= range.max_sample_rate().0
+            }) {
+                let config = range.clone().with_sample_rate(cpal::SampleRate(rate));
+                info!("Trying config: {:?}", config);
+                return Ok(config.into());
+            }
+        }
 
-        for config_range in supported_configs {
-            let config = config_range.with_max_sample_rate();
+        // Otherwise, a range matching just the requested format at its max
+        // supported rate.
+        if let Some(format) = wanted_format {
+            if let Some(range) = supported_configs
+                .iter()
+                .find(|range| range.sample_format() == format)
+            {
+                let config = range.clone().with_max_sample_rate();
+                info!("Trying config: {:?}", config);
+                return Ok(config.into());
+            }
+        }
+
+        for config_range in &supported_configs {
+            let config = config_range.clone().with_max_sample_rate();
             info!("Trying config: {:?}", config);
 
             // Check if the config is supported
@@ -112,3 +184,12 @@ impl ConfigManager {
             .map_err(|e| AudioError::Device(format!("No supported config found: {}", e)).into())
     }
 }
+
+fn parse_sample_format(s: &str) -> Option<cpal::SampleFormat> {
+    match s.to_lowercase().as_str() {
+        "f32" => Some(cpal::SampleFormat::F32),
+        "i16" => Some(cpal::SampleFormat::I16),
+        "u16" => Some(cpal::SampleFormat::U16),
+        _ => None,
+    }
+}
@@ -101,6 +101,9 @@ pub enum StorageError {
 
     #[error("S3 error: {0}")]
     S3(#[from] S3Error),
+
+    #[error("Encryption error: {0}")]
+    Encryption(String),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -122,7 +125,19 @@ pub enum LocalError {
 }
 
 #[derive(thiserror::Error, Debug)]
-pub enum DBError {}
+pub enum DBError {
+    #[error("Failed to open segment index database: {0}")]
+    Open(String),
+
+    #[error("Failed to run segment index migration: {0}")]
+    Migration(String),
+
+    #[error("Segment index query failed: {0}")]
+    Query(String),
+
+    #[error("Failed to (de)serialize a segment index row: {0}")]
+    Serialization(String),
+}
 
 #[derive(thiserror::Error, Debug)]
 pub enum S3Error {
@@ -2,6 +2,7 @@ use crate::prelude::*;
 
 mod app_state;
 mod audio;
+mod cli;
 mod config;
 mod error;
 mod prelude;
@@ -16,6 +17,16 @@ use tokio::time::Duration;
 #[tokio::main(flavor = "multi_thread", worker_threads = 4)]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
+
+    // Offline CLI verbs (`record`/`play`) bypass the server entirely so a
+    // clip can be captured or auditioned without standing up the whole app.
+    let args: Vec<String> = std::env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("record") => return cli::record(&args[2..]).await,
+        Some("play") => return cli::play(&args[2..]).await,
+        _ => {}
+    }
+
     tracing::info!("Starting Life-Logging audio recording service");
 
     let app_state = Arc::new(AppState::new().await?);
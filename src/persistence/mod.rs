@@ -1,10 +0,0 @@
-mod local_storage;
-mod s3_storage;
-mod storage;
-mod storage_manager;
-
-pub use disk_storage::DiskStorage;
-pub use local_storage::LocalStorage;
-pub use s3_storage::S3Storage;
-pub use storage::Storage;
-pub use storage_manager::StorageManager;
@@ -1,12 +1,16 @@
 use crate::app_state::AppState;
+use crate::audio::analysis::{magnitude_to_db, Spectrogram};
 use crate::audio::encoder::{AudioEncoder, ENCODER_FACTORY};
 use crate::audio::visualizer::AudioVisualizer;
 use axum::{
+    body::Bytes,
     extract::{Query, State},
     http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
+use base64::Engine;
+use std::sync::atomic::Ordering;
 use cpal::traits::{DeviceTrait, HostTrait};
 use serde::Deserialize;
 use serde_json::json;
@@ -99,14 +103,29 @@ pub async fn get_audio(
 
 async fn encode_and_respond(
     state: Arc<AppState>,
-    encoder: &dyn AudioEncoder,
+    encoder: &'static dyn AudioEncoder,
     duration: Option<Duration>,
 ) -> Response {
-    let audio_buffer = state.audio_buffer.read().unwrap();
-    let data = audio_buffer.read(duration);
-    let sample_rate = audio_buffer.get_sample_rate();
-    match encoder.encode(&data, sample_rate) {
-        Ok(encoded_data) => {
+    // Clone the samples out of the buffer and release the read lock immediately,
+    // so the CPU/subprocess-bound encode doesn't hold it (or a Tokio worker).
+    let (mut data, mut sample_rate) = {
+        let audio_buffer = state.audio_buffer.read().unwrap();
+        (audio_buffer.read(duration), audio_buffer.get_sample_rate())
+    };
+
+    // Fixed-rate encoders (e.g. Moshi at 24kHz) need the buffer resampled to
+    // their required rate before encoding.
+    if let Some(required) = encoder.required_sample_rate() {
+        if required != sample_rate {
+            data = crate::audio::encoder::resample(&data, sample_rate, required);
+            sample_rate = required;
+        }
+    }
+
+    let encoded = tokio::task::spawn_blocking(move || encoder.encode(&data, sample_rate)).await;
+
+    match encoded {
+        Ok(Ok(encoded_data)) => {
             info!("Successfully encoded {} bytes of audio", encoded_data.len());
             (
                 StatusCode::OK,
@@ -118,7 +137,7 @@ async fn encode_and_respond(
             )
                 .into_response()
         }
-        Err(e) => {
+        Ok(Err(e)) => {
             error!("Failed to encode audio: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -127,6 +146,15 @@ async fn encode_and_respond(
             )
                 .into_response()
         }
+        Err(e) => {
+            error!("Encoding task failed to join: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                [(header::CONTENT_TYPE, "application/json")],
+                Json(json!({"error": "Encoding task failed"})),
+            )
+                .into_response()
+        }
     }
 }
 
@@ -156,6 +184,49 @@ pub async fn visualize_audio(State(state): State<Arc<AppState>>) -> impl IntoRes
     )
 }
 
+#[utoipa::path(
+    get,
+    path = "/spectrogram",
+    params(
+        ("duration" = Option<f32>, Query, description = "Seconds of buffered audio to analyze (default: 10)")
+    ),
+    responses(
+        (status = 200, description = "STFT spectrogram of the requested window", body = serde_json::Value)
+    ),
+    tag = "audio"
+)]
+pub async fn spectrogram(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Json<serde_json::Value> {
+    let duration = params
+        .get("duration")
+        .and_then(|d| d.parse::<f32>().ok())
+        .map(Duration::from_secs_f32)
+        .unwrap_or(Duration::from_secs(10));
+
+    let (data, sample_rate) = {
+        let audio_buffer = state.audio_buffer.read().unwrap();
+        (
+            audio_buffer.read(Some(duration)),
+            audio_buffer.get_sample_rate(),
+        )
+    };
+
+    let spectrogram = Spectrogram::default();
+    let frames: Vec<Vec<f32>> = spectrogram
+        .magnitudes(&data)
+        .into_iter()
+        .map(|frame| frame.into_iter().map(magnitude_to_db).collect())
+        .collect();
+
+    Json(json!({
+        "sample_rate": sample_rate,
+        "frequencies": spectrogram.bin_frequencies(sample_rate),
+        "frames_db": frames,
+    }))
+}
+
 #[utoipa::path(
     get,
     path = "/list_devices",
@@ -172,12 +243,16 @@ pub async fn list_audio_devices() -> Json<serde_json::Value> {
         Ok(input_devices) => {
             let devices: Vec<serde_json::Value> = input_devices
                 .filter_map(|device| {
-                    device.name().ok().map(|name| {
-                        json!({
-                            "name": name,
-                            "id": name, // Using name as ID for simplicity
-                        })
-                    })
+                    let name = device.name().ok()?;
+                    // Report the device's default format so clients can pick a
+                    // capture source deliberately.
+                    let default = device.default_input_config().ok();
+                    Some(json!({
+                        "name": name,
+                        "id": name,
+                        "default_sample_rate": default.as_ref().map(|c| c.sample_rate().0),
+                        "channels": default.as_ref().map(|c| c.channels()),
+                    }))
                 })
                 .collect();
 
@@ -197,7 +272,7 @@ pub async fn list_audio_devices() -> Json<serde_json::Value> {
 
 #[derive(Deserialize, utoipa::ToSchema)]
 pub struct ChangeDeviceRequest {
-    _device_id: String,
+    device_id: String,
 }
 
 #[utoipa::path(
@@ -212,13 +287,261 @@ pub struct ChangeDeviceRequest {
     tag = "audio"
 )]
 pub async fn change_audio_device(
-    State(_state): State<Arc<AppState>>,
-    Json(_payload): Json<ChangeDeviceRequest>,
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<ChangeDeviceRequest>,
 ) -> Json<serde_json::Value> {
-    // TODO: Implement changing audio device
+    use crate::audio::processor::DeviceCommand;
+
+    // Resolve the requested device and read back its default config for the
+    // response, so the caller learns the new active format.
+    let host = cpal::default_host();
+    let device = host
+        .input_devices()
+        .ok()
+        .and_then(|mut devices| devices.find(|d| d.name().map(|n| n == payload.device_id).unwrap_or(false)));
+
+    let device = match device {
+        Some(device) => device,
+        None => {
+            return Json(json!({
+                "status": "error",
+                "message": format!("Input device not found: {}", payload.device_id),
+                "code": "DEVICE_NOT_FOUND"
+            }));
+        }
+    };
+
+    let config = device.default_input_config().ok();
+
+    if let Err(e) = state
+        .device_control
+        .send(DeviceCommand::Switch(payload.device_id.clone()))
+        .await
+    {
+        error!("Failed to send device switch command: {}", e);
+        return Json(json!({
+            "status": "error",
+            "message": "Audio stream manager is not running",
+            "code": "UNAVAILABLE"
+        }));
+    }
+
     Json(json!({
-        "status": "error",
-        "message": "This endpoint is not yet implemented",
-        "code": "NOT_IMPLEMENTED"
+        "status": "ok",
+        "device": payload.device_id,
+        "sample_rate": config.as_ref().map(|c| c.sample_rate().0),
+        "channels": config.as_ref().map(|c| c.channels()),
     }))
 }
+
+/// Decode a `PUT /input_audio` body into the canonical mono `Vec<f32>`.
+///
+/// The body may be base64-encoded or raw; WAV containers are parsed via the
+/// WAV decoder, anything else is treated as little-endian f32 PCM.
+fn decode_input_audio(body: &[u8]) -> Result<Vec<f32>, String> {
+    let bytes = match base64::engine::general_purpose::STANDARD.decode(body) {
+        Ok(decoded) => decoded,
+        Err(_) => body.to_vec(),
+    };
+
+    let encoder: &dyn AudioEncoder = if bytes.starts_with(b"RIFF") {
+        ENCODER_FACTORY.get_encoder("wav").unwrap()
+    } else {
+        ENCODER_FACTORY.get_encoder("pcm").unwrap()
+    };
+
+    encoder.decode(&bytes, 0).map_err(|e| e.to_string())
+}
+
+/// `PUT /input_audio` — stage decoded audio for later injection.
+pub async fn put_input_audio(
+    State(state): State<Arc<AppState>>,
+    body: Bytes,
+) -> Json<serde_json::Value> {
+    match decode_input_audio(&body) {
+        Ok(samples) => {
+            let len = samples.len();
+            *state.recording.staged_input.lock().unwrap() = samples;
+            Json(json!({ "status": "ok", "staged_samples": len }))
+        }
+        Err(e) => Json(json!({ "status": "error", "message": e })),
+    }
+}
+
+/// `POST /inject/start` — begin streaming the staged buffer into the buffer.
+pub async fn start_input_injection(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    state.recording.injecting.store(true, Ordering::Relaxed);
+    let audio_buffer = state.audio_buffer.clone();
+    let recording = state.recording.clone();
+    tokio::spawn(async move {
+        crate::audio::processor::inject_staged_audio(audio_buffer, recording).await;
+    });
+    Json(json!({ "status": "ok", "injecting": true }))
+}
+
+/// `POST /inject/stop` — restore live capture.
+pub async fn stop_input_injection(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    state.recording.injecting.store(false, Ordering::Relaxed);
+    Json(json!({ "status": "ok", "injecting": false }))
+}
+
+/// `POST /output/save/start` — begin tee-ing buffer output into a recording.
+pub async fn start_output_save(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    state.recording.output.lock().unwrap().clear();
+    state.recording.output_saving.store(true, Ordering::Relaxed);
+    Json(json!({ "status": "ok", "saving": true }))
+}
+
+/// `POST /output/save/stop` — stop capturing output.
+pub async fn stop_output_save(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    state.recording.output_saving.store(false, Ordering::Relaxed);
+    Json(json!({ "status": "ok", "saving": false }))
+}
+
+/// `GET /output_audio` — return the captured recording, encoded.
+pub async fn get_output_audio(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    let format = params
+        .get("format")
+        .map(|s| s.to_lowercase())
+        .unwrap_or_else(|| "wav".to_string());
+
+    let encoder = match ENCODER_FACTORY.get_encoder(&format) {
+        Some(encoder) => encoder,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                [(header::CONTENT_TYPE, "application/json")],
+                Json(json!({"error": "Unsupported audio format"})),
+            )
+                .into_response()
+        }
+    };
+
+    let samples = state.recording.output.lock().unwrap().clone();
+    let sample_rate = state.audio_buffer.read().unwrap().get_sample_rate();
+
+    // Fixed-rate encoders (e.g. Opus at 48kHz) need the recording resampled
+    // to their required rate before encoding.
+    let (samples, sample_rate) = match encoder.required_sample_rate() {
+        Some(required) if required != sample_rate => (
+            crate::audio::encoder::resample(&samples, sample_rate, required),
+            required,
+        ),
+        _ => (samples, sample_rate),
+    };
+
+    // Off the async runtime, same as `encode_and_respond` — a long output
+    // recording encoded with a slower codec shouldn't stall other requests.
+    let encoded = tokio::task::spawn_blocking(move || encoder.encode(&samples, sample_rate)).await;
+
+    match encoded {
+        Ok(Ok(encoded)) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, encoder.mime_type()),
+                (header::CONTENT_DISPOSITION, encoder.content_disposition()),
+            ],
+            encoded,
+        )
+            .into_response(),
+        Ok(Err(e)) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            [(header::CONTENT_TYPE, "application/json")],
+            Json(json!({"error": format!("Failed to encode output audio: {}", e)})),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            [(header::CONTENT_TYPE, "application/json")],
+            Json(json!({"error": format!("Encode task panicked: {}", e)})),
+        )
+            .into_response(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/stream_audio",
+    responses(
+        (status = 200, description = "Live stream of encoded Opus frames", content_type = "audio/ogg")
+    ),
+    tag = "audio"
+)]
+/// `GET /stream_audio` — continuously encode newly-captured samples and push
+/// them to the client as a chunked sequence of Opus frames for live monitoring.
+pub async fn stream_audio(State(state): State<Arc<AppState>>) -> Response {
+    let encoder = ENCODER_FACTORY.get_encoder("opus").unwrap();
+
+    // Snapshot the current write cursor so the first poll only yields audio
+    // captured after the connection opened.
+    let (mut cursor, sample_rate) = {
+        let buffer = state.audio_buffer.read().unwrap();
+        (buffer.total_written(), buffer.get_sample_rate())
+    };
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<std::result::Result<Bytes, std::io::Error>>(8);
+    let audio_buffer = state.audio_buffer.clone();
+
+    tokio::spawn(async move {
+        // Poll on roughly the Opus frame cadence, flushing each window of new
+        // samples as an encoded packet until the client disconnects.
+        let mut ticker = tokio::time::interval(Duration::from_millis(20));
+        loop {
+            ticker.tick().await;
+
+            let fresh = {
+                let buffer = audio_buffer.read().unwrap();
+                let (samples, next) = buffer.read_since(cursor);
+                cursor = next;
+                samples
+            };
+
+            if fresh.is_empty() {
+                continue;
+            }
+
+            // Opus only accepts 48kHz input; resample other capture rates.
+            let (fresh, rate) = match encoder.required_sample_rate() {
+                Some(required) if required != sample_rate => (
+                    crate::audio::encoder::resample(&fresh, sample_rate, required),
+                    required,
+                ),
+                _ => (fresh, sample_rate),
+            };
+
+            // Off the async runtime: Opus encoding is cheap per-frame but a
+            // tick on a busy runtime still shouldn't stall other requests.
+            let encoded = tokio::task::spawn_blocking(move || encoder.encode(&fresh, rate)).await;
+
+            match encoded {
+                Ok(Ok(packet)) => {
+                    if tx.send(Ok(Bytes::from(packet))).await.is_err() {
+                        break; // client gone
+                    }
+                }
+                Ok(Err(e)) => {
+                    error!("Failed to encode streamed audio: {}", e);
+                    break;
+                }
+                Err(e) => {
+                    error!("Encode task panicked: {}", e);
+                    break;
+                }
+            }
+        }
+    });
+
+    let stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, encoder.mime_type()),
+            (header::CONTENT_DISPOSITION, "inline"),
+        ],
+        axum::body::Body::from_stream(stream),
+    )
+        .into_response()
+}
@@ -19,6 +19,7 @@ use utoipa_swagger_ui::SwaggerUi;
         handlers::health_check,
         handlers::get_audio,
         handlers::visualize_audio,
+        handlers::spectrogram,
         handlers::list_audio_devices,
         handlers::change_audio_device,
     ),
@@ -58,8 +59,16 @@ pub fn create_router(app_state: Arc<AppState>) -> Router {
         .route("/health", get(handlers::health_check))
         .route("/get_audio", get(handlers::get_audio))
         .route("/visualize_audio", get(handlers::visualize_audio))
+        .route("/spectrogram", get(handlers::spectrogram))
         .route("/list_devices", get(handlers::list_audio_devices))
         .route("/change_device", post(handlers::change_audio_device))
+        .route("/input_audio", axum::routing::put(handlers::put_input_audio))
+        .route("/inject/start", post(handlers::start_input_injection))
+        .route("/inject/stop", post(handlers::stop_input_injection))
+        .route("/output/save/start", post(handlers::start_output_save))
+        .route("/output/save/stop", post(handlers::stop_output_save))
+        .route("/output_audio", get(handlers::get_output_audio))
+        .route("/stream_audio", get(handlers::stream_audio))
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
         .layer(axum::middleware::from_fn(logging_middleware))
         .with_state(app_state)
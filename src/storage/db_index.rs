@@ -0,0 +1,158 @@
+//! Segment index: a SQLite-backed record of every persisted audio segment's
+//! time range, codec, size, and storage location, so the server can answer
+//! "give me audio between these two timestamps" without scanning the
+//! filesystem (or listing a bucket) on every request.
+
+use crate::error::DBError;
+use crate::prelude::*;
+use chrono::{DateTime, TimeZone, Utc};
+use rusqlite::Connection;
+use tokio::sync::Mutex;
+
+/// Where a segment's encoded bytes actually live, as recorded alongside its
+/// index row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SegmentLocation {
+    Local(String),
+    S3(String),
+}
+
+/// One row of the segment index: the time window a persisted chunk of audio
+/// covers, how it was encoded, and where to go fetch it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentRecord {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub codec: String,
+    pub byte_size: u64,
+    pub location: SegmentLocation,
+}
+
+pub struct DbIndex {
+    conn: Mutex<Connection>,
+}
+
+impl DbIndex {
+    /// Open (creating if necessary) the segment index database at `path` and
+    /// run its migration.
+    pub fn open(path: &std::path::Path) -> Result<Self> {
+        let conn = Connection::open(path)
+            .map_err(|e| DBError::Open(e.to_string()))
+            .map_err(crate::error::StorageError::DB)?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS segments (
+                id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                start_ts    INTEGER NOT NULL,
+                end_ts      INTEGER NOT NULL,
+                codec       TEXT NOT NULL,
+                byte_size   INTEGER NOT NULL,
+                location_kind TEXT NOT NULL,
+                location_value TEXT NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| DBError::Migration(e.to_string()))
+        .map_err(crate::error::StorageError::DB)?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS segments_start_ts ON segments(start_ts)",
+            [],
+        )
+        .map_err(|e| DBError::Migration(e.to_string()))
+        .map_err(crate::error::StorageError::DB)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Record an index row for a segment that was just flushed to storage.
+    pub async fn record_segment(&self, record: &SegmentRecord) -> Result<()> {
+        let (location_kind, location_value) = match &record.location {
+            SegmentLocation::Local(path) => ("local", path.clone()),
+            SegmentLocation::S3(key) => ("s3", key.clone()),
+        };
+
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO segments (start_ts, end_ts, codec, byte_size, location_kind, location_value)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                record.start.timestamp_millis(),
+                record.end.timestamp_millis(),
+                record.codec,
+                record.byte_size as i64,
+                location_kind,
+                location_value,
+            ],
+        )
+        .map_err(|e| DBError::Query(e.to_string()))
+        .map_err(crate::error::StorageError::DB)?;
+
+        Ok(())
+    }
+
+    /// All segments whose time window overlaps `[start, end]`, ordered by
+    /// start time.
+    pub async fn segments_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<SegmentRecord>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn
+            .prepare(
+                "SELECT start_ts, end_ts, codec, byte_size, location_kind, location_value
+                 FROM segments
+                 WHERE start_ts <= ?2 AND end_ts >= ?1
+                 ORDER BY start_ts ASC",
+            )
+            .map_err(|e| DBError::Query(e.to_string()))
+            .map_err(crate::error::StorageError::DB)?;
+
+        let rows = stmt
+            .query_map(
+                rusqlite::params![start.timestamp_millis(), end.timestamp_millis()],
+                |row| {
+                    let start_ts: i64 = row.get(0)?;
+                    let end_ts: i64 = row.get(1)?;
+                    let codec: String = row.get(2)?;
+                    let byte_size: i64 = row.get(3)?;
+                    let location_kind: String = row.get(4)?;
+                    let location_value: String = row.get(5)?;
+                    Ok((start_ts, end_ts, codec, byte_size, location_kind, location_value))
+                },
+            )
+            .map_err(|e| DBError::Query(e.to_string()))
+            .map_err(crate::error::StorageError::DB)?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            let (start_ts, end_ts, codec, byte_size, location_kind, location_value) =
+                row.map_err(|e| DBError::Query(e.to_string()))
+                    .map_err(crate::error::StorageError::DB)?;
+
+            let location = match location_kind.as_str() {
+                "local" => SegmentLocation::Local(location_value),
+                "s3" => SegmentLocation::S3(location_value),
+                other => {
+                    return Err(DBError::Serialization(format!(
+                        "unknown segment location kind: {other}"
+                    ))
+                    .into())
+                }
+            };
+
+            records.push(SegmentRecord {
+                start: Utc.timestamp_millis_opt(start_ts).unwrap(),
+                end: Utc.timestamp_millis_opt(end_ts).unwrap(),
+                codec,
+                byte_size: byte_size as u64,
+                location,
+            });
+        }
+
+        Ok(records)
+    }
+}
@@ -0,0 +1,169 @@
+use crate::error::StorageError;
+use crate::prelude::*;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+use super::Storage;
+
+/// A reversible byte transform applied to objects on their way to and from a
+/// [`Storage`] backend.
+pub trait Cipher: Send + Sync {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>>;
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Lets an `Arc<C>` stand in for `C` itself, so one cipher instance can be
+/// shared across multiple `EncryptedStorage` wrappers (e.g. local and S3)
+/// without re-deriving it from the configured key for each backend.
+impl<C: Cipher> Cipher for std::sync::Arc<C> {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        (**self).encrypt(plaintext)
+    }
+
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        (**self).decrypt(ciphertext)
+    }
+}
+
+/// Decorator that encrypts every object before it reaches the wrapped backend
+/// and decrypts it on the way back, leaving the backend itself untouched.
+pub struct EncryptedStorage<S: Storage, C: Cipher> {
+    inner: S,
+    cipher: C,
+}
+
+impl<S: Storage, C: Cipher> EncryptedStorage<S, C> {
+    pub fn new(inner: S, cipher: C) -> Self {
+        Self { inner, cipher }
+    }
+
+    /// The wrapped backend, for callers that need backend-specific
+    /// operations (e.g. computing the path/key a segment was saved under)
+    /// that aren't part of the `Storage` trait itself.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+}
+
+impl<S: Storage, C: Cipher> Storage for EncryptedStorage<S, C> {
+    async fn save(&self, data: &[u8], timestamp: DateTime<Utc>) -> Result<()> {
+        let ciphertext = self.cipher.encrypt(data)?;
+        self.inner.save(&ciphertext, timestamp).await
+    }
+
+    async fn retrieve(&self, timestamp: DateTime<Utc>) -> Result<Vec<u8>> {
+        let ciphertext = self.inner.retrieve(timestamp).await?;
+        self.cipher.decrypt(&ciphertext)
+    }
+
+    async fn cleanup(&self, retention_period: Duration) -> Result<()> {
+        self.inner.cleanup(retention_period).await
+    }
+}
+
+/// ChaCha20-Poly1305 AEAD cipher. A fresh random 12-byte nonce is generated per
+/// object and prepended to the ciphertext so `decrypt` is self-describing.
+pub struct ChaCha20Poly1305Cipher {
+    cipher: chacha20poly1305::ChaCha20Poly1305,
+}
+
+impl ChaCha20Poly1305Cipher {
+    const NONCE_LEN: usize = 12;
+
+    /// Build a cipher from a 32-byte key.
+    pub fn new(key: &[u8; 32]) -> Self {
+        use chacha20poly1305::KeyInit;
+        Self {
+            cipher: chacha20poly1305::ChaCha20Poly1305::new(key.into()),
+        }
+    }
+
+    /// Build a cipher from a hex-encoded 32-byte key, as configured in
+    /// `Config::encryption`.
+    pub fn from_hex_key(hex: &str) -> Result<Self> {
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<std::result::Result<Vec<u8>, _>>()
+            .map_err(|e| StorageError::Encryption(format!("Invalid key hex: {}", e)))?;
+
+        let key: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| StorageError::Encryption("Key must be 32 bytes".to_string()))?;
+
+        Ok(Self::new(&key))
+    }
+
+    /// Build a cipher from the hex-encoded 32-byte key in the legacy
+    /// `LIFELOGGING_ENCRYPTION_KEY` env var, returning `None` when it is
+    /// unset. Superseded by `Config::encryption`, kept for deployments that
+    /// haven't migrated their env vars into `config/*.toml` yet.
+    pub fn from_env() -> Result<Option<Self>> {
+        match std::env::var("LIFELOGGING_ENCRYPTION_KEY") {
+            Ok(hex) => Self::from_hex_key(&hex).map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+impl Cipher for ChaCha20Poly1305Cipher {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        use chacha20poly1305::aead::Aead;
+
+        let mut nonce = [0u8; Self::NONCE_LEN];
+        getrandom::getrandom(&mut nonce)
+            .map_err(|e| StorageError::Encryption(format!("Failed to sample nonce: {}", e)))?;
+
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce.into(), plaintext)
+            .map_err(|e| StorageError::Encryption(format!("AEAD encrypt failed: {}", e)))?;
+
+        let mut out = Vec::with_capacity(Self::NONCE_LEN + ciphertext.len());
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        use chacha20poly1305::aead::Aead;
+
+        if ciphertext.len() < Self::NONCE_LEN {
+            return Err(StorageError::Encryption("Ciphertext too short".to_string()).into());
+        }
+
+        let (nonce, body) = ciphertext.split_at(Self::NONCE_LEN);
+        let nonce: [u8; Self::NONCE_LEN] = nonce.try_into().unwrap();
+
+        self.cipher
+            .decrypt(&nonce.into(), body)
+            .map_err(|e| StorageError::Encryption(format!("AEAD decrypt failed: {}", e)).into())
+    }
+}
+
+/// Trivial repeating-key XOR cipher. Useful only for tests and local
+/// round-trip checks; it provides no real confidentiality.
+pub struct XorCipher {
+    key: Vec<u8>,
+}
+
+impl XorCipher {
+    pub fn new(key: Vec<u8>) -> Self {
+        assert!(!key.is_empty(), "XOR key must not be empty");
+        Self { key }
+    }
+}
+
+impl Cipher for XorCipher {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        Ok(plaintext
+            .iter()
+            .zip(self.key.iter().cycle())
+            .map(|(b, k)| b ^ k)
+            .collect())
+    }
+
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        self.encrypt(ciphertext)
+    }
+}
@@ -1,16 +1,21 @@
 use crate::error::StorageError;
 use crate::prelude::*;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use chrono::{Datelike, Timelike};
 use std::collections::VecDeque;
 use std::fs;
 use std::path::PathBuf;
 use std::time::Duration;
 use tokio::sync::Mutex;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use super::Storage;
 
+/// A request further than this from every recorded segment is treated as
+/// outside any recording window rather than silently returning whatever
+/// happens to be the nearest (possibly hours-old) file.
+const MAX_RETRIEVAL_GAP_SECS: i64 = 3600;
+
 pub struct LocalStorage {
     storage_path: PathBuf,
     format: String,
@@ -20,13 +25,61 @@ pub struct LocalStorage {
 impl LocalStorage {
     pub fn new(storage_path: PathBuf, format: String) -> Result<Self> {
         fs::create_dir_all(&storage_path).map_err(StorageError::DirectoryCreation)?;
+        let local_files = Self::scan_existing_files(&storage_path, &format);
         Ok(Self {
             storage_path,
             format,
-            local_files: Mutex::new(VecDeque::new()),
+            local_files: Mutex::new(local_files),
         })
     }
 
+    /// Rebuild the in-memory index from whatever `audio_*.<format>` files
+    /// already exist under `storage_path`, so a restart doesn't orphan every
+    /// segment persisted before it (previously `local_files` only ever held
+    /// what this process itself had written since startup).
+    fn scan_existing_files(
+        storage_path: &PathBuf,
+        format: &str,
+    ) -> VecDeque<(DateTime<Utc>, PathBuf)> {
+        let entries = match fs::read_dir(storage_path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                warn!("Could not scan {:?} for existing segments: {}", storage_path, e);
+                return VecDeque::new();
+            }
+        };
+
+        let mut files: Vec<(DateTime<Utc>, PathBuf)> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+                let stem = path.file_stem()?.to_str()?;
+                let ext = path.extension()?.to_str()?;
+                if ext != format {
+                    return None;
+                }
+                let timestamp_str = stem.strip_prefix("audio_")?;
+                let naive = NaiveDateTime::parse_from_str(timestamp_str, "%Y%m%d_%H%M%S").ok()?;
+                Some((naive.and_utc(), path))
+            })
+            .collect();
+
+        files.sort_by_key(|(timestamp, _)| *timestamp);
+        info!(
+            "Recovered {} existing local audio segment(s) from {:?}",
+            files.len(),
+            storage_path
+        );
+        files.into()
+    }
+
+    /// The path `save` will write to for a segment captured at `timestamp`,
+    /// for callers (e.g. the segment index) that need to record it alongside
+    /// the data itself.
+    pub fn path_for(&self, timestamp: &DateTime<Utc>) -> PathBuf {
+        self.storage_path.join(self.generate_filename(timestamp))
+    }
+
     fn generate_filename(&self, timestamp: &DateTime<Utc>) -> String {
         format!(
             "audio_{year:04}{month:02}{day:02}_{hour:02}{minute:02}{second:02}.{ext}",
@@ -55,14 +108,24 @@ impl Storage for LocalStorage {
     }
 
     async fn retrieve(&self, timestamp: DateTime<Utc>) -> Result<Vec<u8>> {
-        let local_files = self.local_files.lock().await;
-        let file_path = local_files
-            .iter()
-            .find(|(file_timestamp, _)| *file_timestamp <= timestamp)
-            .map(|(_, path)| path.clone())
+        let mut local_files = self.local_files.lock().await;
+        // `local_files` is kept sorted ascending by timestamp (appended in
+        // order by `save`, recovered in order by `scan_existing_files`), so
+        // the entry immediately before the first one past `timestamp` is the
+        // closest one at or before it.
+        let files = local_files.make_contiguous();
+        let split = files.partition_point(|(file_timestamp, _)| *file_timestamp <= timestamp);
+
+        let (file_timestamp, file_path) = files
+            .get(split.wrapping_sub(1))
+            .filter(|_| split > 0)
             .ok_or_else(|| StorageError::FileNotFound(timestamp.to_string()))?;
 
-        fs::read(&file_path).map_err(|e| StorageError::FileRead(e.to_string()).into())
+        if timestamp - *file_timestamp > chrono::Duration::seconds(MAX_RETRIEVAL_GAP_SECS) {
+            return Err(StorageError::FileNotFound(timestamp.to_string()).into());
+        }
+
+        fs::read(file_path).map_err(|e| StorageError::FileRead(e.to_string()).into())
     }
 
     async fn cleanup(&self, retention_period: Duration) -> Result<()> {
@@ -1,7 +1,11 @@
+mod db_index;
+mod encrypted_storage;
 mod local_storage;
 mod s3_storage;
 mod storage_manager;
 
+pub use db_index::{DbIndex, SegmentLocation, SegmentRecord};
+pub use encrypted_storage::{ChaCha20Poly1305Cipher, Cipher, EncryptedStorage, XorCipher};
 pub use local_storage::LocalStorage;
 pub use s3_storage::S3Storage;
 pub use storage_manager::StorageManager;
@@ -1,15 +1,24 @@
 use crate::error::{S3Error, StorageError};
 use crate::prelude::*;
 use aws_sdk_s3::primitives::ByteStream;
-use aws_sdk_s3::types::StorageClass;
+use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart, StorageClass};
 use aws_sdk_s3::{config::Region, Client};
 use chrono::{DateTime, Utc};
 use chrono::{Datelike, Timelike};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::time::Duration;
+use tempfile::NamedTempFile;
 use tracing::info;
 
 use super::Storage;
 
+/// Objects at or above this size are uploaded in parts (see
+/// [`S3Storage::save_multipart`]) instead of buffered whole into a single
+/// `put_object` request; 8MiB comfortably clears S3's 5MiB minimum part
+/// size while keeping small clips on the simple single-shot path.
+const MULTIPART_THRESHOLD: usize = 8 * 1024 * 1024;
+const PART_SIZE: usize = 8 * 1024 * 1024;
+
 pub struct S3Storage {
     client: Client,
     bucket: String,
@@ -31,6 +40,13 @@ impl S3Storage {
         })
     }
 
+    /// The key `save` will upload to for a segment captured at `timestamp`,
+    /// for callers (e.g. the segment index) that need to record it alongside
+    /// the data itself.
+    pub fn key_for(&self, timestamp: &DateTime<Utc>) -> String {
+        self.generate_key(timestamp)
+    }
+
     fn generate_key(&self, timestamp: &DateTime<Utc>) -> String {
         format!(
             "{}/{year:04}/{month:02}/{day:02}/audio_{hour:02}{minute:02}{second:02}.opus",
@@ -43,22 +59,134 @@ impl S3Storage {
             second = timestamp.second()
         )
     }
-}
 
-impl Storage for S3Storage {
-    async fn save(&self, data: &[u8], timestamp: DateTime<Utc>) -> Result<()> {
-        let key = self.generate_key(&timestamp);
-        let body = ByteStream::from(data.to_vec());
+    /// Upload `data` to `key` in parts: `data` is spilled to a temp file
+    /// first, then each part is read back off disk one `PART_SIZE` chunk at
+    /// a time rather than sliced from `data`. The caller still passes the
+    /// whole encoded clip in memory, so peak memory use grows with clip
+    /// length and is not bounded by `PART_SIZE`.
+    async fn save_multipart(&self, key: &str, data: &[u8]) -> Result<()> {
+        let mut temp_file = NamedTempFile::new()
+            .map_err(|e| StorageError::S3(S3Error::S3Upload(format!("temp file: {}", e))))?;
+        temp_file
+            .write_all(data)
+            .map_err(|e| StorageError::S3(S3Error::S3Upload(format!("temp file write: {}", e))))?;
 
-        self.client
-            .put_object()
+        let create = self
+            .client
+            .create_multipart_upload()
             .bucket(&self.bucket)
-            .key(&key)
-            .body(body)
+            .key(key)
             .storage_class(StorageClass::GlacierIr)
             .send()
             .await
             .map_err(|e| StorageError::S3(S3Error::S3Upload(e.to_string())))?;
+        let upload_id = create
+            .upload_id()
+            .ok_or_else(|| StorageError::S3(S3Error::S3Upload("missing upload id".into())))?;
+
+        let result = self.upload_parts(key, upload_id, &mut temp_file, data.len()).await;
+
+        match result {
+            Ok(parts) => {
+                self.client
+                    .complete_multipart_upload()
+                    .bucket(&self.bucket)
+                    .key(key)
+                    .upload_id(upload_id)
+                    .multipart_upload(
+                        CompletedMultipartUpload::builder()
+                            .set_parts(Some(parts))
+                            .build(),
+                    )
+                    .send()
+                    .await
+                    .map_err(|e| StorageError::S3(S3Error::S3Upload(e.to_string())))?;
+                Ok(())
+            }
+            Err(e) => {
+                let _ = self
+                    .client
+                    .abort_multipart_upload()
+                    .bucket(&self.bucket)
+                    .key(key)
+                    .upload_id(upload_id)
+                    .send()
+                    .await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn upload_parts(
+        &self,
+        key: &str,
+        upload_id: &str,
+        temp_file: &mut NamedTempFile,
+        total_len: usize,
+    ) -> Result<Vec<CompletedPart>> {
+        temp_file
+            .seek(SeekFrom::Start(0))
+            .map_err(|e| StorageError::S3(S3Error::S3Upload(format!("temp file seek: {}", e))))?;
+
+        let mut parts = Vec::with_capacity(total_len.div_ceil(PART_SIZE));
+        let mut chunk = vec![0u8; PART_SIZE];
+        let mut part_number = 1i32;
+
+        loop {
+            let read = temp_file.read(&mut chunk).map_err(|e| {
+                StorageError::S3(S3Error::S3Upload(format!("temp file read: {}", e)))
+            })?;
+            if read == 0 {
+                break;
+            }
+
+            let output = self
+                .client
+                .upload_part()
+                .bucket(&self.bucket)
+                .key(key)
+                .upload_id(upload_id)
+                .part_number(part_number)
+                .body(ByteStream::from(chunk[..read].to_vec()))
+                .send()
+                .await
+                .map_err(|e| StorageError::S3(S3Error::S3Upload(e.to_string())))?;
+
+            let e_tag = output
+                .e_tag()
+                .ok_or_else(|| StorageError::S3(S3Error::S3Upload("missing part e_tag".into())))?;
+            parts.push(
+                CompletedPart::builder()
+                    .e_tag(e_tag)
+                    .part_number(part_number)
+                    .build(),
+            );
+            part_number += 1;
+        }
+
+        Ok(parts)
+    }
+}
+
+impl Storage for S3Storage {
+    async fn save(&self, data: &[u8], timestamp: DateTime<Utc>) -> Result<()> {
+        let key = self.generate_key(&timestamp);
+
+        if data.len() >= MULTIPART_THRESHOLD {
+            self.save_multipart(&key, data).await?;
+        } else {
+            let body = ByteStream::from(data.to_vec());
+            self.client
+                .put_object()
+                .bucket(&self.bucket)
+                .key(&key)
+                .body(body)
+                .storage_class(StorageClass::GlacierIr)
+                .send()
+                .await
+                .map_err(|e| StorageError::S3(S3Error::S3Upload(e.to_string())))?;
+        }
 
         info!("Uploaded audio data to S3: {}", key);
         Ok(())
@@ -1,34 +1,107 @@
-use super::{LocalStorage, S3Storage, Storage};
-use crate::audio::buffer::AudioBuffer;
+use super::{
+    ChaCha20Poly1305Cipher, Cipher, DbIndex, EncryptedStorage, LocalStorage, S3Storage,
+    SegmentLocation, SegmentRecord, Storage,
+};
+use crate::audio::buffer::{ms_to_samples, AudioBuffer};
 use crate::audio::codec::CODEC_FACTORY;
-use crate::error::AudioError;
+use crate::audio::resample::Resampler;
+use crate::error::{AudioError, CodecError};
 use crate::prelude::*;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
 use tokio::time;
 use tracing::{error, info};
 
+/// A storage backend that may or may not be wrapped in transparent at-rest
+/// encryption, so `StorageManager` can hold one of either without the
+/// backend-specific `path_for`/`key_for` helpers (needed for the segment
+/// index) having to go through the object-unsafe `Storage` trait.
+enum MaybeEncrypted<S: Storage, C: Cipher + Clone> {
+    Plain(S),
+    Encrypted(EncryptedStorage<S, C>),
+}
+
+impl<S: Storage, C: Cipher + Clone> MaybeEncrypted<S, C> {
+    fn new(storage: S, cipher: Option<C>) -> Self {
+        match cipher {
+            Some(cipher) => Self::Encrypted(EncryptedStorage::new(storage, cipher)),
+            None => Self::Plain(storage),
+        }
+    }
+}
+
+impl<S: Storage, C: Cipher + Clone> Storage for MaybeEncrypted<S, C> {
+    async fn save(&self, data: &[u8], timestamp: DateTime<Utc>) -> Result<()> {
+        match self {
+            Self::Plain(s) => s.save(data, timestamp).await,
+            Self::Encrypted(s) => s.save(data, timestamp).await,
+        }
+    }
+
+    async fn retrieve(&self, timestamp: DateTime<Utc>) -> Result<Vec<u8>> {
+        match self {
+            Self::Plain(s) => s.retrieve(timestamp).await,
+            Self::Encrypted(s) => s.retrieve(timestamp).await,
+        }
+    }
+
+    async fn cleanup(&self, retention_period: Duration) -> Result<()> {
+        match self {
+            Self::Plain(s) => s.cleanup(retention_period).await,
+            Self::Encrypted(s) => s.cleanup(retention_period).await,
+        }
+    }
+}
+
+impl<C: Cipher + Clone> MaybeEncrypted<LocalStorage, C> {
+    fn path_for(&self, timestamp: &DateTime<Utc>) -> PathBuf {
+        match self {
+            Self::Plain(s) => s.path_for(timestamp),
+            Self::Encrypted(s) => s.inner().path_for(timestamp),
+        }
+    }
+}
+
+impl<C: Cipher + Clone> MaybeEncrypted<S3Storage, C> {
+    fn key_for(&self, timestamp: &DateTime<Utc>) -> String {
+        match self {
+            Self::Plain(s) => s.key_for(timestamp),
+            Self::Encrypted(s) => s.inner().key_for(timestamp),
+        }
+    }
+}
+
 pub struct StorageManager {
-    local_storage: Arc<LocalStorage>,
-    s3_storage: Option<Arc<S3Storage>>,
+    local_storage: Arc<MaybeEncrypted<LocalStorage, Arc<ChaCha20Poly1305Cipher>>>,
+    s3_storage: Option<Arc<MaybeEncrypted<S3Storage, Arc<ChaCha20Poly1305Cipher>>>>,
+    db_index: Arc<DbIndex>,
     local_interval: Duration,
     target_sample_rate: u32,
     format: String,
 }
 
 impl StorageManager {
+    /// `cipher`, when set, transparently encrypts every object written to
+    /// both `local_storage` and `s3_storage` (and decrypts on retrieve),
+    /// shared between the two backends via the `Arc<C>: Cipher` blanket impl
+    /// so the key is only ever derived once.
     pub fn new(
         local_storage: LocalStorage,
         s3_storage: Option<S3Storage>,
+        db_index: DbIndex,
         local_interval: Duration,
         target_sample_rate: u32,
         format: String,
+        cipher: Option<Arc<ChaCha20Poly1305Cipher>>,
     ) -> Self {
         Self {
-            local_storage: Arc::new(local_storage),
-            s3_storage: s3_storage.map(Arc::new),
+            local_storage: Arc::new(MaybeEncrypted::new(local_storage, cipher.clone())),
+            s3_storage: s3_storage
+                .map(|s3| Arc::new(MaybeEncrypted::new(s3, cipher))),
+            db_index: Arc::new(db_index),
             local_interval,
             target_sample_rate,
             format,
@@ -56,16 +129,49 @@ impl StorageManager {
             .get(&self.format)
             .ok_or_else(|| AudioError::UnsupportedFormat(self.format.clone()))?;
 
-        let encoded_data = encoder.encode(&resampled_data, self.target_sample_rate)?;
+        // Encoding is CPU-bound (Moshi in particular runs synchronous candle
+        // inference under a mutex); it would otherwise stall this
+        // task's interval.tick() loop and the HTTP server for the whole
+        // encode, so run it on the blocking pool.
+        let target_sample_rate = self.target_sample_rate;
+        let encoded_data = tokio::task::spawn_blocking(move || {
+            encoder.encode(&resampled_data, target_sample_rate)
+        })
+        .await
+        .map_err(|e| CodecError::Encoding(format!("Encode task panicked: {}", e)))??;
 
         let timestamp = Utc::now();
 
         self.local_storage.save(&encoded_data, timestamp).await?;
+        self.db_index
+            .record_segment(&SegmentRecord {
+                start: timestamp - chrono::Duration::from_std(self.local_interval).unwrap(),
+                end: timestamp,
+                codec: self.format.clone(),
+                byte_size: encoded_data.len() as u64,
+                location: SegmentLocation::Local(
+                    self.local_storage
+                        .path_for(&timestamp)
+                        .to_string_lossy()
+                        .into_owned(),
+                ),
+            })
+            .await?;
 
         match &self.s3_storage {
             Some(s3) => {
                 info!("Attempting to save to S3");
-                s3.save(&encoded_data, timestamp).await?
+                s3.save(&encoded_data, timestamp).await?;
+                self.db_index
+                    .record_segment(&SegmentRecord {
+                        start: timestamp
+                            - chrono::Duration::from_std(self.local_interval).unwrap(),
+                        end: timestamp,
+                        codec: self.format.clone(),
+                        byte_size: encoded_data.len() as u64,
+                        location: SegmentLocation::S3(s3.key_for(&timestamp)),
+                    })
+                    .await?;
             }
             None => info!("S3 storage not configured, skipping S3 upload"),
         }
@@ -73,6 +179,113 @@ impl StorageManager {
         Ok(())
     }
 
+    /// Segments recorded in the index whose window overlaps `[start, end]`,
+    /// so callers can locate audio for a time range without scanning the
+    /// filesystem or listing a bucket.
+    pub async fn segments_between(
+        &self,
+        start: chrono::DateTime<Utc>,
+        end: chrono::DateTime<Utc>,
+    ) -> Result<Vec<SegmentRecord>> {
+        self.db_index.segments_between(start, end).await
+    }
+
+    /// Decode and stitch together the samples covering `[start, end]`,
+    /// drawing on both the live `audio_buffer` and the persisted segment
+    /// index so a window spanning the boundary between them (or several
+    /// persisted segments) comes back as one contiguous clip at
+    /// `target_sample_rate`.
+    pub async fn retrieve_range(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        audio_buffer: Arc<RwLock<AudioBuffer>>,
+    ) -> Result<Vec<f32>> {
+        if end <= start {
+            return Ok(Vec::new());
+        }
+
+        let now = Utc::now();
+        let (live_rate, live_capacity) = {
+            let buffer = audio_buffer.read().await;
+            (buffer.get_sample_rate(), buffer.capacity())
+        };
+        let buffer_floor = now
+            - chrono::Duration::milliseconds(
+                (live_capacity as f64 * 1000.0 / live_rate as f64) as i64,
+            );
+
+        let mut samples = Vec::new();
+
+        // Persisted portion: whatever falls before what the live buffer
+        // still covers.
+        if start < buffer_floor {
+            samples.extend(self.decode_segments(start, end.min(buffer_floor)).await?);
+        }
+
+        // Live portion: whatever falls within the buffer's current window,
+        // expressed as "milliseconds back from now" since the buffer itself
+        // has no notion of absolute wall-clock time.
+        if end > buffer_floor {
+            let window_start = start.max(buffer_floor);
+            let start_ms_ago = (now - window_start).num_milliseconds().max(0) as u64;
+            let end_ms_ago = (now - end).num_milliseconds().max(0) as u64;
+
+            let buffer = audio_buffer.read().await;
+            let live = buffer.read_range_ms(start_ms_ago, end_ms_ago);
+            if live_rate == self.target_sample_rate {
+                samples.extend(live);
+            } else {
+                samples.extend(self.resample(&live, live_rate, self.target_sample_rate));
+            }
+        }
+
+        Ok(samples)
+    }
+
+    /// Decode every indexed segment overlapping `[start, end]` and trim each
+    /// one down to its overlap with the window before concatenating, so a
+    /// request that starts or ends mid-segment doesn't pull in neighboring
+    /// audio.
+    async fn decode_segments(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<f32>> {
+        let segments = self.segments_between(start, end).await?;
+        let mut samples = Vec::new();
+
+        for segment in segments {
+            let codec = CODEC_FACTORY
+                .get(&segment.codec)
+                .ok_or_else(|| AudioError::UnsupportedFormat(segment.codec.clone()))?;
+
+            let bytes = match &segment.location {
+                SegmentLocation::Local(_) => self.local_storage.retrieve(segment.end).await?,
+                SegmentLocation::S3(_) => match &self.s3_storage {
+                    Some(s3) => s3.retrieve(segment.end).await?,
+                    None => continue,
+                },
+            };
+
+            let decoded = codec.decode(&bytes, self.target_sample_rate)?;
+
+            let trim_start_ms = (start.max(segment.start) - segment.start)
+                .num_milliseconds()
+                .max(0) as u64;
+            let trim_end_ms = (segment.end - end.min(segment.end))
+                .num_milliseconds()
+                .max(0) as u64;
+
+            let start_sample = ms_to_samples(trim_start_ms, self.target_sample_rate).min(decoded.len());
+            let end_sample = decoded
+                .len()
+                .saturating_sub(ms_to_samples(trim_end_ms, self.target_sample_rate).min(decoded.len()));
+
+            if start_sample < end_sample {
+                samples.extend_from_slice(&decoded[start_sample..end_sample]);
+            }
+        }
+
+        Ok(samples)
+    }
+
     pub async fn start_persistence_task(self: Arc<Self>, audio_buffer: Arc<RwLock<AudioBuffer>>) {
         let mut interval = time::interval(self.local_interval);
         loop {
@@ -94,30 +307,11 @@ impl StorageManager {
         }
     }
 
+    /// Band-limited windowed-sinc resampling (see [`Resampler`]) so that
+    /// downsampling before encode (e.g. 48kHz capture down to a 16kHz
+    /// target) doesn't alias, the way plain linear interpolation would.
     fn resample(&self, data: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
-        if from_rate == to_rate {
-            return data.to_vec();
-        }
-
-        if data.is_empty() {
-            return Vec::new();
-        }
-
-        let ratio = from_rate as f32 / to_rate as f32;
-        let new_len = (data.len() as f32 / ratio).ceil() as usize;
-        let mut resampled = Vec::with_capacity(new_len);
-
-        for i in 0..new_len {
-            let pos = i as f32 * ratio;
-            let index = (pos.floor() as usize).min(data.len() - 1);
-            let next_index = (index + 1).min(data.len() - 1);
-            let frac = pos - pos.floor();
-
-            let sample = data[index] * (1.0 - frac) + data[next_index] * frac;
-            resampled.push(sample);
-        }
-
-        resampled
+        Resampler::new(from_rate, to_rate, 16).process(data)
     }
 
     pub async fn cleanup(&self, local_retention: Duration, s3_retention: Duration) -> Result<()> {